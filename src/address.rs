@@ -0,0 +1,98 @@
+use crate::errors::BaseError;
+use crate::keccak::keccak256;
+use crate::opts::Base;
+use num::{bigint::BigInt, Num, Signed};
+
+/// Number of hex digits in an Ethereum address
+const DIGITS: usize = 40;
+
+/// Writes the value as an EIP-55 checksummed Ethereum address. Each letter is uppercase
+/// if the matching nibble of the Keccak-256 hash of the lowercase address is 8 or more.
+pub fn to_address(value: &BigInt) -> Result<String, BaseError> {
+    if value.is_negative() || value.bits() > 160 {
+        return Err(BaseError::OverflowError {
+            message: "Ethereum addresses are 160 bits",
+        });
+    }
+    Ok(format!(
+        "0x{}",
+        checksum(&format!("{:0>40}", value.to_str_radix(16)))
+    ))
+}
+
+fn checksum(digits: &str) -> String {
+    let hash = keccak256(digits.as_bytes());
+    digits
+        .chars()
+        .enumerate()
+        .map(|(index, c)| {
+            let nibble = (hash[index / 2] >> (4 * (1 - index % 2))) & 0xf;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Reads a 0x-prefixed address of 40 hex digits. Mixed case addresses must have a valid
+/// EIP-55 checksum; all lowercase or all uppercase addresses carry no checksum.
+pub fn parse(value: &str) -> Result<BigInt, BaseError> {
+    let digits = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .filter(|digits| digits.len() == DIGITS && digits.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or(BaseError::InvalidFormat {
+            base: Base::Address,
+        })?;
+
+    let lowercase = digits.to_ascii_lowercase();
+    let mixed = digits != lowercase && digits != digits.to_ascii_uppercase();
+    if mixed && checksum(&lowercase) != digits {
+        return Err(BaseError::InvalidChecksum);
+    }
+    BigInt::from_str_radix(&lowercase, 16).map_err(|_| BaseError::InvalidFormat {
+        base: Base::Address,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Addresses from the EIP-55 specification
+    const ADDRESSES: [&str; 4] = [
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn checksums_spec_addresses() {
+        for address in ADDRESSES {
+            let value = BigInt::from_str_radix(&address[2..].to_ascii_lowercase(), 16).unwrap();
+            assert_eq!(to_address(&value).unwrap(), address);
+            assert_eq!(parse(address).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_checksums() {
+        assert!(parse("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").is_err());
+        assert!(parse("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").is_ok());
+        assert!(parse("0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED").is_ok());
+        assert!(parse("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA").is_err());
+    }
+
+    #[test]
+    fn pads_short_addresses() {
+        assert_eq!(
+            to_address(&BigInt::from(1)).unwrap(),
+            "0x0000000000000000000000000000000000000001"
+        );
+        assert!(to_address(&(BigInt::from(1) << 160)).is_err());
+        assert!(to_address(&BigInt::from(-1)).is_err());
+    }
+}
@@ -0,0 +1,46 @@
+use crate::base::Value;
+use crate::errors::BaseError;
+use crate::opts::Opt;
+use crate::{format_output, parse_number};
+use num::{bigint::BigInt, Integer, One, Signed, Zero};
+
+/// Prints the value rounded up, or down, to a multiple of the boundary
+pub fn run(opt: &Opt, value: &str, boundary: &str, down: bool) -> Result<(), BaseError> {
+    let parsed = parse_number(opt, value, None)?;
+    let boundary = parse_number(opt, boundary, None)?
+        .integer()?
+        .clone()
+        .into_inner();
+    if !boundary.is_positive() {
+        return Err(BaseError::ArgError {
+            message: "Alignment boundary must be positive",
+        });
+    }
+
+    let num = parsed.integer()?.clone().into_inner();
+    let aligned = if down {
+        num.div_floor(&boundary) * &boundary
+    } else {
+        num.div_ceil(&boundary) * &boundary
+    };
+    println!(
+        "{}",
+        format_output(opt, &parsed.with_value(Value::new(aligned)))?
+    );
+    Ok(())
+}
+
+/// Returns the smallest power of two greater than or equal to the value
+pub fn next_power_of_two(num: &Value) -> Result<Value, BaseError> {
+    let num = num.clone().into_inner();
+    if num.is_negative() {
+        return Err(BaseError::ArgError {
+            message: "--next-pow2 cannot be used with negative values",
+        });
+    }
+    if num.is_zero() {
+        return Ok(Value::new(BigInt::one()));
+    }
+    let power = BigInt::one() << (&num - 1u32).bits();
+    Ok(Value::new(power))
+}
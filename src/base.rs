@@ -1,116 +1,989 @@
+use crate::address;
 use crate::errors::BaseError;
-use crate::opts::Base;
-use num::{bigint::BigUint, Num};
+use crate::exotic;
+use crate::expr::MAX_BITS;
+use crate::hex;
+use crate::hexdump;
+use crate::net;
+use crate::opts::{Base, Endian};
+use crate::perm;
+use crate::roman;
+use crate::size;
+use crate::uuid;
+use num::{
+    bigint::{BigInt, BigUint},
+    Num, One, Signed, ToPrimitive,
+};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops;
+use std::str::FromStr;
 
+/// Defines checked conversions to primitive integer types, failing with the number of
+/// bits the value requires
+macro_rules! to_primitive {
+    ($($method:ident $ty:ident $signed:expr),*) => {$(
+        pub fn $method(&self) -> Result<$ty, BaseError> {
+            ToPrimitive::$method(&self.value).ok_or(BaseError::Overflow {
+                bits_required: self.bits_required($signed),
+            })
+        }
+    )*};
+}
+
+/// Value is an arbitrary precision integer read from, and written to, any base
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Value {
-    value: BigUint,
+    value: BigInt,
 }
 
 impl Value {
-    pub fn from(value: String, base: Base) -> Result<Value, BaseError> {
-        Value::validate(base.clone(), value.clone())?;
+    pub fn new(value: BigInt) -> Value {
+        Value { value }
+    }
 
-        match base {
-            Base::Bin => BigUint::from_str_radix(value.as_str(), 2),
-            Base::Oct => BigUint::from_str_radix(value.as_str(), 8),
-            Base::Dec => BigUint::from_str_radix(value.as_str(), 10),
-            Base::Hex => BigUint::from_str_radix(value.trim_start_matches("0x"), 16),
+    pub fn into_inner(self) -> BigInt {
+        self.value
+    }
+
+    /// Returns the number of bits needed to represent the magnitude, at least one
+    pub fn bits(&self) -> u64 {
+        self.value.bits().max(1)
+    }
+
+    /// Returns the number of set bits in the magnitude
+    pub fn popcount(&self) -> u64 {
+        self.value.magnitude().count_ones()
+    }
+
+    /// Returns the index of the lowest set bit, or None for zero
+    pub fn trailing_zeros(&self) -> Option<u64> {
+        self.value.trailing_zeros()
+    }
+
+    /// Returns the index of the highest set bit, or None for zero
+    pub fn highest_bit(&self) -> Option<u64> {
+        self.value.bits().checked_sub(1)
+    }
+
+    /// Returns the number of bits an integer type needs to hold the value. Signed types
+    /// need a sign bit, and negative values always need a signed type.
+    pub fn bits_required(&self, signed: bool) -> u64 {
+        if self.value.is_negative() {
+            (-&self.value - 1u32).bits() + 1
+        } else if signed {
+            self.value.bits() + 1
+        } else {
+            self.value.bits().max(1)
         }
-        .map_err(|_| Value::get_parse_error(base))
-        .map(|value| Value { value })
     }
 
-    pub fn to_base(&self, base: Base) -> String {
+    to_primitive!(
+        to_u8 u8 false,
+        to_u16 u16 false,
+        to_u32 u32 false,
+        to_u64 u64 false,
+        to_u128 u128 false,
+        to_usize usize false,
+        to_i8 i8 true,
+        to_i16 i16 true,
+        to_i32 i32 true,
+        to_i64 i64 true,
+        to_i128 i128 true,
+        to_isize isize true
+    );
+
+    pub fn from(value: &str, base: Base) -> Result<Value, BaseError> {
         match base {
-            Base::Bin => self.value.to_str_radix(2),
-            Base::Oct => self.value.to_str_radix(8),
-            Base::Dec => self.value.to_str_radix(10),
-            Base::Hex => self.value.to_str_radix(16),
+            Base::Roman => return roman::parse(value).map(Value::new),
+            Base::Balanced3 => {
+                return exotic::parse_balanced_ternary(value)
+                    .map(Value::new)
+                    .ok_or_else(|| Value::get_parse_error(base))
+            }
+            Base::Neg2 => {
+                return exotic::parse_negabinary(value)
+                    .map(Value::new)
+                    .ok_or_else(|| Value::get_parse_error(base))
+            }
+            Base::Col => {
+                return exotic::parse_column(value)
+                    .map(Value::new)
+                    .ok_or_else(|| Value::get_parse_error(base))
+            }
+            Base::Hms => {
+                return exotic::parse_mixed_radix(value, &exotic::HMS)
+                    .map(Value::new)
+                    .ok_or_else(|| Value::get_parse_error(base))
+            }
+            Base::Factorial => {
+                return exotic::parse_factorial(value)
+                    .map(Value::new)
+                    .ok_or_else(|| Value::get_parse_error(base))
+            }
+            Base::Doz => {
+                return exotic::parse_dozenal(value)
+                    .map(Value::new)
+                    .ok_or_else(|| Value::get_parse_error(base))
+            }
+            Base::Ip4 => {
+                return net::parse_ip4(value)
+                    .map(Value::new)
+                    .ok_or_else(|| Value::get_parse_error(base))
+            }
+            Base::Ip6 => {
+                return net::parse_ip6(value)
+                    .map(Value::new)
+                    .ok_or_else(|| Value::get_parse_error(base))
+            }
+            Base::Perm => {
+                return perm::parse_perm(value)
+                    .map(Value::new)
+                    .ok_or_else(|| Value::get_parse_error(base))
+            }
+            Base::Uuid => {
+                return uuid::parse_uuid(value)
+                    .map(Value::new)
+                    .ok_or_else(|| Value::get_parse_error(base))
+            }
+            Base::Size => return size::parse(value, false).map(Value::new),
+            Base::Address => return address::parse(value).map(Value::new),
+            Base::Hexdump => {
+                return hexdump::parse(value)
+                    .map(Value::new)
+                    .ok_or_else(|| Value::get_parse_error(base))
+            }
+            _ => {}
+        }
+
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, value),
+        };
+        let unprefixed = strip_base_prefix(digits, &base);
+        let offset = value.len() - unprefixed.len();
+        let digits = strip_base_suffix(unprefixed, &base);
+        // long hex values are decoded a chunk at a time, checking the digits as they go
+        let decoded = match base {
+            Base::Hex => hex::decode(digits),
+            _ => None,
+        };
+        let magnitude = match decoded {
+            Some(magnitude) => magnitude,
+            None => {
+                Value::validate(&base, digits, offset)?;
+                let radix = radix(base.clone()).unwrap();
+                BigUint::from_str_radix(digits, radix).map_err(|_| Value::get_parse_error(base))?
+            }
+        };
+        let value = BigInt::from(magnitude);
+        Ok(Value {
+            value: if negative { -value } else { value },
+        })
+    }
+
+    /// Wraps the value into the `width`-bit two's complement bit pattern
+    pub fn to_unsigned(&self, width: usize) -> Result<Value, BaseError> {
+        let modulus = Value::modulus(width)?;
+        if self.value.is_negative() {
+            if self.value < -(&modulus >> 1usize) {
+                return Err(Value::get_width_error());
+            }
+            Ok(Value {
+                value: &self.value + modulus,
+            })
+        } else if self.value >= modulus {
+            Err(Value::get_width_error())
+        } else {
+            Ok(Value {
+                value: self.value.clone(),
+            })
         }
     }
 
-    fn validate(base: Base, value: String) -> Result<(), BaseError> {
-        if match base {
-            Base::Bin => is_valid_bin(value),
-            Base::Oct => is_valid_oct(value),
-            Base::Dec => is_valid_dec(value),
-            Base::Hex => is_valid_hex(value),
-        } {
-            Ok(())
+    /// Interprets the value as a `width`-bit two's complement number
+    pub fn to_signed(&self, width: usize) -> Result<Value, BaseError> {
+        let unsigned = self.to_unsigned(width)?;
+        if unsigned.value.bit(width as u64 - 1) {
+            Ok(Value {
+                value: unsigned.value - Value::modulus(width)?,
+            })
         } else {
-            Err(Value::get_parse_error(base))
+            Ok(unsigned)
+        }
+    }
+
+    pub fn to_base(&self, base: Base) -> Result<String, BaseError> {
+        match base {
+            Base::Bin => Ok(self.value.to_str_radix(2)),
+            Base::Oct => Ok(self.value.to_str_radix(8)),
+            Base::Dec => Ok(self.value.to_str_radix(10)),
+            Base::Hex => {
+                let digits = hex::encode(self.value.magnitude());
+                if self.value.is_negative() {
+                    Ok(format!("-{}", digits))
+                } else {
+                    Ok(digits)
+                }
+            }
+            Base::Roman => roman::to_roman(&self.value, false),
+            Base::Balanced3 => Ok(exotic::to_balanced_ternary(&self.value)),
+            Base::Neg2 => Ok(exotic::to_negabinary(&self.value)),
+            Base::Col => exotic::to_column(&self.value),
+            Base::Hms => Ok(exotic::to_mixed_radix(&self.value, &exotic::HMS)),
+            Base::Factorial => Ok(exotic::to_factorial(&self.value)),
+            Base::Doz => Ok(exotic::to_dozenal(&self.value)),
+            Base::Ip4 => net::to_ip4(&self.value, false),
+            Base::Ip6 => net::to_ip6(&self.value),
+            Base::Perm => perm::to_perm(&self.value),
+            Base::Uuid => uuid::to_uuid(&self.value),
+            Base::Size => size::to_size(&self.value, false),
+            Base::Address => address::to_address(&self.value),
+            Base::Hexdump => Ok(hexdump::to_hexdump(self, hexdump::DEFAULT_WIDTH)),
+        }
+    }
+
+    /// Returns the bytes of the value in the given byte order, as two's complement if negative
+    pub fn to_bytes(&self, endian: Endian) -> Vec<u8> {
+        match (endian, self.value.is_negative()) {
+            (Endian::Be, false) => self.value.to_bytes_be().1,
+            (Endian::Le, false) => self.value.to_bytes_le().1,
+            (Endian::Be, true) => self.value.to_signed_bytes_be(),
+            (Endian::Le, true) => self.value.to_signed_bytes_le(),
+        }
+    }
+
+    /// Reverses the byte order of the value as a `width`-bit number
+    pub fn swap_bytes(&self, width: usize) -> Result<Value, BaseError> {
+        if !width.is_multiple_of(8) {
+            return Err(BaseError::ArgError {
+                message: "Byte swap width must be a multiple of 8",
+            });
+        }
+
+        let mut bytes = self.to_unsigned(width)?.to_bytes(Endian::Le);
+        bytes.resize(width / 8, 0);
+        Ok(Value {
+            value: BigInt::from(BigUint::from_bytes_be(&bytes)),
+        })
+    }
+
+    /// Formats the value, left-padding it with zeros to the digit count of a `width`-bit number
+    pub fn to_padded_base(&self, base: Base, width: usize) -> Result<String, BaseError> {
+        let digits = self.to_base(base.clone())?;
+        if self.value.is_negative() {
+            return Ok(digits);
+        }
+        Ok(pad(&digits, digits_for_bits(base, width)))
+    }
+
+    fn modulus(width: usize) -> Result<BigInt, BaseError> {
+        if width == 0 {
+            return Err(BaseError::ArgError {
+                message: "Width must be greater than zero",
+            });
+        }
+        if width as u64 > MAX_BITS {
+            return Err(BaseError::ArgError {
+                message: "Width: must be at most 2^24 bits",
+            });
+        }
+        Ok(BigInt::one() << width)
+    }
+
+    /// Checks the digits of a positional base, which start `offset` characters into the value
+    fn validate(base: &Base, digits: &str, offset: usize) -> Result<(), BaseError> {
+        if digits.is_empty() {
+            return Err(BaseError::EmptyInput);
+        }
+        if let Some(other) = prefixed_base(digits) {
+            if !is_valid(base.clone(), digits) {
+                return Err(BaseError::InvalidPrefix {
+                    prefix: digits[..digits.len() - strip_base_prefix(digits, &other).len()]
+                        .to_string(),
+                    base: base.clone(),
+                });
+            }
+        }
+        match invalid_digit(digits, base, offset, false) {
+            Some(error) => Err(error),
+            None => Ok(()),
         }
     }
 
     fn get_parse_error(base: Base) -> BaseError {
-        return match base {
-            Base::Bin => BaseError::ParseError {
-                message: "Binary: only include the digits 0 or 1.",
-            },
-            Base::Oct => BaseError::ParseError {
-                message: "Octal: only enter the digits 0-7.",
-            },
-            Base::Dec => BaseError::ParseError {
-                message: "Decimal: only enter the digits 0-9",
-            },
-            Base::Hex => BaseError::ParseError {
-                message: "Hexaxecimal: only enter the digita 0-9 and a-f",
-            },
-        };
+        BaseError::InvalidFormat { base }
+    }
+
+    fn get_width_error() -> BaseError {
+        BaseError::OverflowError {
+            message: "Value does not fit in the given width",
+        }
+    }
+}
+
+impl FromStr for Value {
+    type Err = BaseError;
+
+    /// Parses the value in its detected base, e.g. 255, 0xff or 11111111b
+    fn from_str(value: &str) -> Result<Value, BaseError> {
+        let base = detect_base(value, DetectStrategy::Default)?;
+        Value::from(value, base)
+    }
+}
+
+impl TryFrom<&str> for Value {
+    type Error = BaseError;
+
+    fn try_from(value: &str) -> Result<Value, BaseError> {
+        value.parse()
+    }
+}
+
+/// Writes the value in decimal
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.value, f)
+    }
+}
+
+impl fmt::LowerHex for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.value, f)
+    }
+}
+
+impl fmt::UpperHex for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.value, f)
+    }
+}
+
+impl fmt::Binary for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Binary::fmt(&self.value, f)
+    }
+}
+
+impl fmt::Octal for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Octal::fmt(&self.value, f)
+    }
+}
+
+/// Implements a binary operator for owned and borrowed values by delegating to the
+/// inner integer
+macro_rules! impl_binary_op {
+    ($($trait:ident $method:ident),*) => {$(
+        impl ops::$trait for Value {
+            type Output = Value;
+
+            fn $method(self, other: Value) -> Value {
+                Value::new(ops::$trait::$method(self.value, other.value))
+            }
+        }
+
+        impl<'a> ops::$trait<&'a Value> for &'a Value {
+            type Output = Value;
+
+            fn $method(self, other: &'a Value) -> Value {
+                Value::new(ops::$trait::$method(&self.value, &other.value))
+            }
+        }
+    )*};
+}
+
+// division and remainder by zero panic, as they do for the inner integer
+impl_binary_op!(Add add, Sub sub, Mul mul, Div div, Rem rem, BitAnd bitand, BitOr bitor, BitXor bitxor);
+
+impl ops::Shl<usize> for Value {
+    type Output = Value;
+
+    fn shl(self, bits: usize) -> Value {
+        Value::new(self.value << bits)
+    }
+}
+
+/// Shifts right arithmetically, so negative values round towards negative infinity
+impl ops::Shr<usize> for Value {
+    type Output = Value;
+
+    fn shr(self, bits: usize) -> Value {
+        Value::new(self.value >> bits)
+    }
+}
+
+impl ops::Neg for Value {
+    type Output = Value;
+
+    fn neg(self) -> Value {
+        Value::new(-self.value)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Base {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+/// Reads a base by name, in any case, e.g. "hex" or "Roman"
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Base {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Base, D::Error> {
+        let name = <Cow<str>>::deserialize(deserializer)?;
+        name.parse().map_err(|_| {
+            serde::de::Error::invalid_value(serde::de::Unexpected::Str(&name), &"a base name")
+        })
+    }
+}
+
+/// Writes the value as a decimal string, so values of any size survive formats with
+/// 64-bit or floating point numbers
+#[cfg(feature = "serde")]
+impl serde::Serialize for Value {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Reads a value from a decimal string, or from an integer
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Value {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Value;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a decimal string or an integer")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Value, E> {
+                Value::from(value, Base::Dec)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(value), &self))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<Value, E> {
+                Ok(Value::new(BigInt::from(value)))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<Value, E> {
+                Ok(Value::new(BigInt::from(value)))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+/// Returns the number of digits needed to show every bit of a `bits`-bit number.
+/// Only binary, octal and hex digits line up with bits, so no padding applies to other bases.
+pub fn digits_for_bits(base: Base, bits: usize) -> usize {
+    match base {
+        Base::Bin => bits,
+        Base::Oct => bits.div_ceil(3),
+        Base::Hex => bits.div_ceil(4),
+        _ => 0,
+    }
+}
+
+/// Left-pads the integer digits with zeros to at least `len` digits, keeping any sign in front
+pub fn pad(digits: &str, len: usize) -> String {
+    let (sign, digits) = match digits.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", digits),
+    };
+    let whole = digits.find('.').unwrap_or(digits.len());
+    let zeros = len.saturating_sub(whole);
+    format!("{}{}{}", sign, "0".repeat(zeros), digits)
+}
+
+/// Returns the conventional digit group size, i.e. nibbles for binary and hex, thousands for decimal.
+/// Numerals and formats such as hms or ip4 are not grouped.
+pub fn default_group_size(base: &Base) -> usize {
+    match base {
+        Base::Bin | Base::Hex | Base::Neg2 => 4,
+        Base::Oct | Base::Dec | Base::Balanced3 | Base::Doz => 3,
+        _ => usize::MAX,
+    }
+}
+
+/// Separates the integer digits into groups of `size`, counting from the least significant digit
+pub fn group(digits: &str, size: usize, separator: &str) -> String {
+    let (sign, groups, fractional) = split_groups(digits, size);
+    format!("{}{}{}", sign, groups.join(separator), fractional)
+}
+
+/// Groups the whole digits like `group`, alternating the color of every other group
+/// so group boundaries are visible without a separator
+pub fn color_groups(digits: &str, size: usize, separator: &str) -> String {
+    let (sign, groups, fractional) = split_groups(digits, size);
+    let count = groups.len();
+    let groups: Vec<String> = groups
+        .into_iter()
+        .enumerate()
+        .map(|(index, group)| {
+            // the least significant group keeps the default color
+            if (count - index).is_multiple_of(2) {
+                format!("\x1b[36m{}\x1b[0m", group)
+            } else {
+                group
+            }
+        })
+        .collect();
+    format!("{}{}{}", sign, groups.join(separator), fractional)
+}
+
+/// Splits the digits into their sign, groups of whole digits counted from the right, and
+/// fractional digits
+fn split_groups(digits: &str, size: usize) -> (&str, Vec<String>, &str) {
+    let (sign, digits) = match digits.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", digits),
+    };
+    let (whole, fractional) = match digits.find('.') {
+        Some(index) => digits.split_at(index),
+        None => (digits, ""),
+    };
+
+    let chars: Vec<char> = whole.chars().collect();
+    let groups = chars
+        .rchunks(size.max(1))
+        .rev()
+        .map(|chunk| chunk.iter().collect())
+        .collect();
+    (sign, groups, fractional)
+}
+
+/// Inserts the prefix in front of the digits, after any sign
+pub fn prefix(digits: &str, prefix: &str) -> String {
+    match digits.strip_prefix('-') {
+        Some(digits) => format!("-{}{}", prefix, digits),
+        None => format!("{}{}", prefix, digits),
     }
 }
 
-fn is_valid_bin(value: String) -> bool {
+/// Returns the radix of a conventional positional base, or None for other numeral systems
+pub fn radix(base: Base) -> Option<u32> {
+    match base {
+        Base::Bin => Some(2),
+        Base::Oct => Some(8),
+        Base::Dec => Some(10),
+        Base::Hex => Some(16),
+        _ => None,
+    }
+}
+
+pub fn is_valid(base: Base, value: &str) -> bool {
+    match base {
+        Base::Bin => is_valid_bin(value),
+        Base::Oct => is_valid_oct(value),
+        Base::Dec => is_valid_dec(value),
+        Base::Hex => is_valid_hex(value),
+        Base::Roman => roman::is_valid(value),
+        Base::Balanced3 => exotic::parse_balanced_ternary(value).is_some(),
+        Base::Neg2 => exotic::parse_negabinary(value).is_some(),
+        Base::Col => exotic::parse_column(value).is_some(),
+        Base::Hms => exotic::parse_mixed_radix(value, &exotic::HMS).is_some(),
+        Base::Factorial => exotic::parse_factorial(value).is_some(),
+        Base::Doz => exotic::parse_dozenal(value).is_some(),
+        Base::Ip4 => net::parse_ip4(value).is_some(),
+        Base::Ip6 => net::parse_ip6(value).is_some(),
+        Base::Perm => perm::parse_perm(value).is_some(),
+        Base::Uuid => uuid::parse_uuid(value).is_some(),
+        Base::Size => size::parse(value, false).is_ok(),
+        Base::Address => address::parse(value).is_ok(),
+        Base::Hexdump => hexdump::is_hexdump(value),
+    }
+}
+
+fn is_valid_bin(value: &str) -> bool {
     for c in value.chars() {
         if !(c == '0' || c == '1') {
             return false;
         }
     }
-    return true;
+    true
 }
 
-fn is_valid_oct(value: String) -> bool {
+fn is_valid_oct(value: &str) -> bool {
     for c in value.chars() {
         if !("01234567".contains(c)) {
             return false;
         }
     }
-    return true;
+    true
 }
 
-fn is_valid_dec(value: String) -> bool {
+fn is_valid_dec(value: &str) -> bool {
     for c in value.chars() {
         if !("0123456789".contains(c)) {
             return false;
         }
     }
-    return true;
+    true
 }
 
-fn is_valid_hex(value: String) -> bool {
-    for c in value.to_lowercase().chars() {
-        if !("0123456789abcdefx".contains(c)) {
+fn is_valid_hex(value: &str) -> bool {
+    for c in value.chars() {
+        if !(c.is_ascii_hexdigit() || c == 'x' || c == 'X') {
             return false;
         }
     }
-    return true;
+    true
+}
+
+/// DetectStrategy selects how `detect_base` picks a base for values without an explicit input base
+#[derive(Debug, Clone, PartialEq)]
+pub enum DetectStrategy {
+    /// Use the base prefix or suffix if present, otherwise try decimal, then hex
+    Default,
+    /// Only accept values with a 0x, 0b or 0o prefix, or an h, b, o, q or d suffix
+    Strict,
+    /// Use the base prefix or suffix if present, otherwise prefer the given base, then decimal and hex
+    Prefer(Base),
+    /// Use the base prefix or suffix if present, otherwise try binary, octal, decimal, then hex
+    Legacy,
 }
 
-pub fn detect_base(value: String) -> Result<Base, BaseError> {
-    if is_valid_bin(value.clone()) {
-        return Ok(Base::Bin);
+/// Strips the conventional prefix of the given base, e.g. 0x for hex
+pub fn strip_base_prefix<'a>(value: &'a str, base: &Base) -> &'a str {
+    let prefix = match base {
+        Base::Bin => "0b",
+        Base::Oct => "0o",
+        Base::Hex => "0x",
+        _ => return value,
     };
-    if is_valid_oct(value.clone()) {
-        return Ok(Base::Oct);
+    value.strip_prefix(prefix).unwrap_or(value)
+}
+
+/// Strips an assembly-style radix suffix of the given base, e.g. the h in FFh.
+/// Only suffixes that are not digits of the base itself are stripped.
+pub fn strip_base_suffix<'a>(value: &'a str, base: &Base) -> &'a str {
+    let suffixes: &[char] = match base {
+        Base::Bin => &['b', 'B'],
+        Base::Oct => &['o', 'O', 'q', 'Q'],
+        Base::Dec => &['d', 'D'],
+        Base::Hex => &['h', 'H'],
+        _ => &[],
     };
-    if is_valid_dec(value.clone()) {
-        return Ok(Base::Dec);
+    value.strip_suffix(suffixes).unwrap_or(value)
+}
+
+/// Returns the base named by the value's assembly-style suffix, e.g. FFh, 1010b, 777o or 255d,
+/// if it has one and the digits before it are valid
+pub fn suffixed_base(value: &str) -> Option<Base> {
+    vec![Base::Hex, Base::Oct, Base::Bin, Base::Dec]
+        .into_iter()
+        .find(|base| {
+            let digits = strip_base_suffix(value, base);
+            !digits.is_empty() && digits.len() < value.len() && is_valid(base.clone(), digits)
+        })
+}
+
+/// Returns the base named by the value's prefix, if it has one and the digits after it are
+/// valid. Hex digits may end in a binary exponent, as in the hex float 0x18p-1.
+fn prefixed_base(value: &str) -> Option<Base> {
+    vec![Base::Hex, Base::Bin, Base::Oct]
+        .into_iter()
+        .find(|base| {
+            let digits = strip_base_prefix(value, base);
+            let digits = match (base, digits.split_once('p')) {
+                (Base::Hex, Some((mantissa, exponent)))
+                    if !mantissa.is_empty() && is_exponent(exponent) =>
+                {
+                    mantissa
+                }
+                _ => digits,
+            };
+            digits.len() < value.len() && is_valid(base.clone(), digits)
+        })
+}
+
+/// Returns true if the value is a decimal exponent, optionally signed
+fn is_exponent(value: &str) -> bool {
+    let digits = value.strip_prefix(&['+', '-'][..]).unwrap_or(value);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Returns an error for the first character of the digits that is not a digit of the
+/// positional base, skipping a single fraction point if `fraction` is set
+fn invalid_digit(digits: &str, base: &Base, offset: usize, fraction: bool) -> Option<BaseError> {
+    let radix = radix(base.clone())?;
+    let mut point = !fraction;
+    digits
+        .chars()
+        .enumerate()
+        .find(|(_, c)| match c {
+            '.' if !point => {
+                point = true;
+                false
+            }
+            c => !c.is_digit(radix),
+        })
+        .map(|(position, digit)| BaseError::InvalidDigit {
+            digit,
+            position: offset + position,
+            base: base.clone(),
+        })
+}
+
+/// Drops the first fraction point from the value, borrowing it if it has none
+fn without_point(value: &str) -> Cow<'_, str> {
+    if value.contains('.') {
+        Cow::Owned(value.replacen('.', "", 1))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+pub fn detect_base(original: &str, strategy: DetectStrategy) -> Result<Base, BaseError> {
+    let value = without_point(original.trim_start_matches('-'));
+    if value.is_empty() {
+        return Err(BaseError::EmptyInput);
+    }
+    if hexdump::is_hexdump(original) {
+        return Ok(Base::Hexdump);
+    }
+    // suffixes that are also hex digits, as in 1b or 100d, only name the base of values
+    // that are not valid hex, unless detection is strict
+    let strict = strategy == DetectStrategy::Strict;
+    let suffixed = || suffixed_base(&value).filter(|_| strict || !is_valid(Base::Hex, &value));
+    if let Some(base) = prefixed_base(&value).or_else(suffixed) {
+        return Ok(base);
     };
-    if is_valid_hex(value) {
-        return Ok(Base::Hex);
+
+    let order = match strategy {
+        DetectStrategy::Default => vec![Base::Dec, Base::Hex],
+        DetectStrategy::Strict => return Err(BaseError::AmbiguousBase),
+        DetectStrategy::Prefer(base) => vec![base, Base::Dec, Base::Hex],
+        DetectStrategy::Legacy => vec![Base::Bin, Base::Oct, Base::Dec, Base::Hex],
     };
 
-    Err(BaseError::ParseError {
-        message: "Unable to detect base",
+    if let Some(base) = order
+        .into_iter()
+        .find(|base| is_valid(base.clone(), &value))
+    {
+        return Ok(base);
+    }
+
+    // report the first character that is not a digit of the prefixed base, or of hex as
+    // the widest base tried, which fails detection rather than parsing
+    let digits = original.trim_start_matches('-');
+    let prefixed = vec![Base::Hex, Base::Bin, Base::Oct]
+        .into_iter()
+        .map(|base| {
+            let unprefixed = strip_base_prefix(digits, &base);
+            (base, unprefixed)
+        })
+        .find(|(_, unprefixed)| unprefixed.len() < digits.len());
+    let (base, unprefixed) = prefixed.clone().unwrap_or((Base::Hex, digits));
+    let offset = original.len() - unprefixed.len();
+    Err(match invalid_digit(unprefixed, &base, offset, true) {
+        Some(BaseError::InvalidDigit {
+            digit, position, ..
+        }) if prefixed.is_none() => BaseError::UndetectedDigit { digit, position },
+        Some(error) => error,
+        None => BaseError::NoValidBase,
     })
 }
+
+/// Explains why `detect_base` picked the given base, noting other bases the value is also valid in
+pub fn explain_detection(value: &str, base: Base) -> String {
+    let value = without_point(value.trim_start_matches('-'));
+    let reason = if prefixed_base(&value).is_some() {
+        match base {
+            Base::Bin => "it starts with 0b",
+            Base::Oct => "it starts with 0o",
+            _ => "it starts with 0x",
+        }
+    } else if suffixed_base(&value) == Some(base.clone()) {
+        match base {
+            Base::Bin => "it ends with b",
+            Base::Oct => "it ends with o or q",
+            Base::Dec => "it ends with d",
+            Base::Hex => "it ends with h",
+            _ => unreachable!(),
+        }
+    } else {
+        match base {
+            Base::Bin => "it only contains the digits 0 and 1",
+            Base::Oct => "it only contains the digits 0-7",
+            Base::Dec => "it only contains the digits 0-9",
+            Base::Hex => "it contains the digits a-f",
+            Base::Roman => "it is written in Roman numerals",
+            Base::Balanced3 => "it only contains the digits T, 0 and 1",
+            Base::Neg2 => "it only contains the digits 0 and 1",
+            Base::Col => "it only contains the letters A-Z",
+            Base::Hms => "it is written as H:MM:SS",
+            Base::Factorial => "each digit is at most its position",
+            Base::Doz => "it only contains the digits 0-9, X and E",
+            Base::Ip4 => "it is a dotted-quad IPv4 address",
+            Base::Ip6 => "it is a colon-hex IPv6 address",
+            Base::Perm => "it is a symbolic permission mode",
+            Base::Uuid => "it is a UUID",
+            Base::Size => "it is a size such as 4K or 2MiB",
+            Base::Address => "it is an Ethereum address",
+            Base::Hexdump => "it is xxd or hexdump -C output",
+        }
+    };
+
+    let others: Vec<&str> = Base::all()
+        .into_iter()
+        .filter(|other| *other != base && is_valid(other.clone(), &value))
+        .map(|other| other.name())
+        .collect();
+    if others.is_empty() {
+        format!("{} because {}", base.repr(), reason)
+    } else {
+        format!(
+            "{} because {}, but it is also valid as {}",
+            base.repr(),
+            reason,
+            others.join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_hex_floats() {
+        for value in &["0x1.8p0", "0x1.8p-1", "0x1p+4", "-0x1.8p3", "0x1.8"] {
+            assert_eq!(
+                detect_base(value, DetectStrategy::Default).unwrap(),
+                Base::Hex
+            );
+        }
+        assert!(detect_base("0x1p", DetectStrategy::Default).is_err());
+        assert!(detect_base("0xp1", DetectStrategy::Default).is_err());
+        assert!(detect_base("0x1pz", DetectStrategy::Default).is_err());
+    }
+
+    #[test]
+    fn detects_suffixes_that_are_hex_digits_only_when_strict() {
+        let detect = |value| detect_base(value, DetectStrategy::Default).unwrap();
+        assert_eq!(detect("1b"), Base::Hex);
+        assert_eq!(detect("100d"), Base::Hex);
+        assert_eq!(detect("FFh"), Base::Hex);
+        assert_eq!(detect("777o"), Base::Oct);
+        assert_eq!(detect("12q"), Base::Oct);
+
+        let strict = |value| detect_base(value, DetectStrategy::Strict).unwrap();
+        assert_eq!(strict("1b"), Base::Bin);
+        assert_eq!(strict("100d"), Base::Dec);
+    }
+
+    fn value(value: i64) -> Value {
+        Value::new(BigInt::from(value))
+    }
+
+    #[test]
+    fn parses_and_formats_with_std_traits() {
+        assert_eq!("0xff".parse::<Value>().unwrap(), value(255));
+        assert_eq!(Value::try_from("0b101").unwrap(), value(5));
+        assert_eq!("-0o17".parse::<Value>().unwrap(), value(-15));
+        assert!("0xfg".parse::<Value>().is_err());
+        assert!(Value::try_from("").is_err());
+
+        assert_eq!(value(255).to_string(), "255");
+        assert_eq!(
+            format!("{:x} {:X} {:#x}", value(255), value(255), value(255)),
+            "ff FF 0xff"
+        );
+        assert_eq!(
+            format!(
+                "{:b} {:#b} {:o} {:#o}",
+                value(5),
+                value(5),
+                value(8),
+                value(8)
+            ),
+            "101 0b101 10 0o10"
+        );
+        assert_eq!(
+            format!("{:>6} {:08b} {:+}", value(42), value(5), value(1)),
+            "    42 00000101 +1"
+        );
+        assert_eq!(format!("{:x}", value(-255)), "-ff");
+    }
+
+    #[test]
+    fn applies_operators() {
+        let (a, b) = (value(12), value(10));
+        assert_eq!(&a + &b, value(22));
+        assert_eq!(&a - &b, value(2));
+        assert_eq!(&a * &b, value(120));
+        assert_eq!(&a / &b, value(1));
+        assert_eq!(&a % &b, value(2));
+        assert_eq!(&a & &b, value(8));
+        assert_eq!(&a | &b, value(14));
+        assert_eq!(&a ^ &b, value(6));
+        assert_eq!(a.clone() + b.clone(), value(22));
+        assert_eq!(a.clone() ^ b, value(6));
+        assert_eq!(-a.clone(), value(-12));
+        assert_eq!(a.clone() << 4, value(192));
+        assert_eq!(a >> 2, value(3));
+        // division truncates, and right shifts round down
+        assert_eq!(&value(-7) / &value(2), value(-3));
+        assert_eq!(&value(-7) % &value(2), value(-1));
+        assert_eq!(value(-7) >> 1, value(-4));
+    }
+
+    #[test]
+    fn converts_to_primitives() {
+        assert_eq!(value(255).to_u8().unwrap(), 255);
+        assert_eq!(value(-128).to_i8().unwrap(), -128);
+        assert_eq!(value(-1).to_i128().unwrap(), -1);
+        assert_eq!(
+            Value::new(BigInt::from(u64::MAX)).to_u64().unwrap(),
+            u64::MAX
+        );
+        assert_eq!(value(0).to_usize().unwrap(), 0);
+
+        let bits_required = |result: Result<_, BaseError>| match result {
+            Err(BaseError::Overflow { bits_required }) => bits_required,
+            _ => panic!("expected an overflow"),
+        };
+        assert_eq!(bits_required(value(256).to_u8().map(drop)), 9);
+        assert_eq!(bits_required(value(128).to_i8().map(drop)), 9);
+        assert_eq!(bits_required(value(-129).to_i8().map(drop)), 9);
+        // negative values report the bits of the signed type that holds them
+        assert_eq!(bits_required(value(-1).to_u8().map(drop)), 1);
+        assert_eq!(bits_required(value(-256).to_u16().map(drop)), 9);
+        assert_eq!(
+            bits_required(Value::new(BigInt::from(1) << 64).to_u64().map(drop)),
+            65
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn round_trips_through_serde() {
+        use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+
+        assert_tokens(&Base::Hex, &[Token::Str("hex")]);
+        assert_tokens(&Base::Balanced3, &[Token::Str("balanced3")]);
+        assert_de_tokens(&Base::Roman, &[Token::Str("Roman")]);
+        assert_de_tokens_error::<Base>(
+            &[Token::Str("base7")],
+            "invalid value: string \"base7\", expected a base name",
+        );
+
+        let big = Value::new(BigInt::from(u64::MAX) * 1000 + 7);
+        assert_tokens(&big, &[Token::Str("18446744073709551615007")]);
+        assert_tokens(&Value::new(BigInt::from(-42)), &[Token::Str("-42")]);
+        assert_de_tokens(&Value::new(BigInt::from(255)), &[Token::U64(255)]);
+        assert_de_tokens(&Value::new(BigInt::from(-1)), &[Token::I64(-1)]);
+        assert_de_tokens_error::<Value>(
+            &[Token::Str("0xff")],
+            "invalid value: string \"0xff\", expected a decimal string or an integer",
+        );
+    }
+}
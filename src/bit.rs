@@ -0,0 +1,137 @@
+use crate::base::Value;
+use crate::command::BitOp;
+use crate::errors::BaseError;
+use crate::expr::MAX_BITS;
+use crate::opts::{Base, Opt};
+use crate::{format_output, parse_number, Number, Parsed};
+use num::{bigint::BigInt, One, Signed};
+
+/// Runs a single bit manipulation, printing the result in the output base
+pub fn run(opt: &Opt, op: &BitOp) -> Result<(), BaseError> {
+    let value = match op {
+        BitOp::Set { value, .. }
+        | BitOp::Clear { value, .. }
+        | BitOp::Toggle { value, .. }
+        | BitOp::Test { value, .. }
+        | BitOp::Extract { value, .. }
+        | BitOp::Insert { value, .. } => value,
+    };
+    if let BitOp::Set { index, .. }
+    | BitOp::Clear { index, .. }
+    | BitOp::Toggle { index, .. }
+    | BitOp::Test { index, .. } = op
+    {
+        check_index(*index)?;
+    }
+    let parsed = parse_number(opt, value, None)?;
+    let num = parsed.integer()?;
+    if let Some(width) = opt.width.or(parsed.width) {
+        num.to_unsigned(width)?;
+    }
+
+    let result = match op {
+        BitOp::Set { index, .. } => set(num.clone(), *index, true),
+        BitOp::Clear { index, .. } => set(num.clone(), *index, false),
+        BitOp::Toggle { index, .. } => set(num.clone(), *index, !test(num, *index)),
+        BitOp::Test { index, .. } => {
+            println!("{}", test(num, *index) as u8);
+            return Ok(());
+        }
+        BitOp::Extract { range, .. } => {
+            let (hi, lo) = parse_range(range)?;
+            extract(num, hi, lo)
+        }
+        BitOp::Insert { range, field, .. } => {
+            let (hi, lo) = parse_range(range)?;
+            let field = parse_number(opt, field, None)?;
+            insert(num, hi, lo, field.integer()?)?
+        }
+    };
+
+    // with a fixed width, bits outside of it cannot be touched
+    if let Some(width) = opt.width.or(parsed.width) {
+        result.to_unsigned(width)?;
+    }
+    println!("{}", format_output(opt, &parsed.with_value(result))?);
+    Ok(())
+}
+
+/// Fails for a bit index at or above MAX_BITS, so setting it cannot exhaust memory
+fn check_index(index: u64) -> Result<(), BaseError> {
+    if index >= MAX_BITS {
+        Err(BaseError::ArgError {
+            message: "Bit index: must be below 2^24",
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Parses a bit range written as `HI:LO`, a half-open `LO..HI`, or a single bit index.
+/// Both ends must be bit indexes below MAX_BITS.
+pub fn parse_range(range: &str) -> Result<(u64, u64), BaseError> {
+    let error = BaseError::ArgError {
+        message: "Bit ranges are written as HI:LO or LO..HI, e.g. 15:8 or 8..16",
+    };
+    let (hi, lo) = if let Some((lo, hi)) = range.split_once("..") {
+        match (lo.trim().parse::<u64>(), hi.trim().parse::<u64>()) {
+            (Ok(lo), Ok(hi)) if hi > lo => (hi - 1, lo),
+            _ => return Err(error),
+        }
+    } else {
+        let (hi, lo) = range.split_once(':').unwrap_or((range, range));
+        match (hi.trim().parse::<u64>(), lo.trim().parse::<u64>()) {
+            (Ok(hi), Ok(lo)) if hi >= lo => (hi, lo),
+            _ => return Err(error),
+        }
+    };
+    check_index(hi)?;
+    Ok((hi, lo))
+}
+
+/// Prints the mask of the bit range in the output base
+pub fn run_mask(opt: &Opt, range: &str) -> Result<(), BaseError> {
+    let (hi, lo) = parse_range(range)?;
+    let parsed = Parsed {
+        base: Base::Hex,
+        num: Number::Integer(Value::new(mask(hi, lo))),
+        width: None,
+        signed: false,
+    };
+    println!("{}", format_output(opt, &parsed)?);
+    Ok(())
+}
+
+/// Returns a mask with bits `hi` down to `lo` set
+pub fn mask(hi: u64, lo: u64) -> BigInt {
+    ((BigInt::one() << (hi - lo + 1)) - 1) << lo
+}
+
+pub fn test(value: &Value, index: u64) -> bool {
+    value.clone().into_inner().bit(index)
+}
+
+pub fn set(value: Value, index: u64, bit: bool) -> Value {
+    let mut value = value.into_inner();
+    value.set_bit(index, bit);
+    Value::new(value)
+}
+
+/// Returns bits `hi` down to `lo` of the value, shifted down to bit zero
+pub fn extract(value: &Value, hi: u64, lo: u64) -> Value {
+    let value = value.clone().into_inner();
+    Value::new((value >> lo) & mask(hi - lo, 0))
+}
+
+/// Replaces bits `hi` down to `lo` of the value with the given field
+pub fn insert(value: &Value, hi: u64, lo: u64, field: &Value) -> Result<Value, BaseError> {
+    let field = field.clone().into_inner();
+    if field.is_negative() || field.bits() > hi - lo + 1 {
+        return Err(BaseError::OverflowError {
+            message: "Field does not fit in the bit range",
+        });
+    }
+
+    let value = value.clone().into_inner();
+    Ok(Value::new((value & !mask(hi, lo)) | (field << lo)))
+}
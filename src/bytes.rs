@@ -0,0 +1,26 @@
+use crate::opts::ByteFormat;
+
+/// Renders bytes as an array or string literal in the given format
+pub fn format_bytes(bytes: &[u8], format: &ByteFormat) -> String {
+    match format {
+        ByteFormat::C => format!("{{{}}}", hex_list(bytes)),
+        ByteFormat::Rust => format!("[{}]", hex_list(bytes)),
+        ByteFormat::Python => format!("b\"{}\"", escape(bytes)),
+        ByteFormat::Escape => escape(bytes),
+    }
+}
+
+fn hex_list(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("0x{:02x}", byte))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn escape(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("\\x{:02x}", byte))
+        .collect()
+}
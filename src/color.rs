@@ -0,0 +1,153 @@
+use crate::base::Value;
+use crate::errors::{BaseError, Syntax};
+use crate::opts::{Base, Opt};
+use crate::{format_output, parse_number, Number, Parsed};
+use num::{bigint::BigInt, ToPrimitive};
+
+/// Color holds red, green and blue channels with an optional alpha channel
+struct Color {
+    channels: [u8; 3],
+    alpha: Option<u8>,
+}
+
+/// Shows a color given as #rgb, #rgba, #rrggbb, #rrggbbaa, rgb(), rgba() or a packed
+/// integer, in each of those forms. Packed integers are 0xRRGGBB, or 0xRRGGBBAA if alpha
+/// is set or the value is above 0xffffff.
+pub fn run(opt: &Opt, value: &str) -> Result<(), BaseError> {
+    let color = match parse_css(value) {
+        Some(color) => color?,
+        None => from_packed(parse_number(opt, value, None)?.integer()?)?,
+    };
+
+    let [red, green, blue] = color.channels;
+    let (hex, css, packed, width) = match color.alpha {
+        Some(alpha) => (
+            format!("#{:02x}{:02x}{:02x}{:02x}", red, green, blue, alpha),
+            format!(
+                "rgba({}, {}, {}, {})",
+                red,
+                green,
+                blue,
+                alpha_fraction(alpha)
+            ),
+            u32::from_be_bytes([red, green, blue, alpha]),
+            32,
+        ),
+        None => (
+            format!("#{:02x}{:02x}{:02x}", red, green, blue),
+            format!("rgb({}, {}, {})", red, green, blue),
+            u32::from_be_bytes([0, red, green, blue]),
+            24,
+        ),
+    };
+    let parsed = Parsed {
+        base: Base::Hex,
+        num: Number::Integer(Value::new(BigInt::from(packed))),
+        width: None,
+        signed: false,
+    };
+    // the channels are padded to their bits, but are not a two's complement value
+    let mut opt = opt.clone();
+    opt.bits = opt.bits.or(Some(width));
+
+    println!("Hex: {}", hex);
+    println!("CSS: {}", css);
+    println!("{}", format_output(&opt, &parsed)?);
+    Ok(())
+}
+
+fn parse_error() -> BaseError {
+    BaseError::InvalidSyntax {
+        syntax: Syntax::Color,
+    }
+}
+
+/// Parses the hex and rgb() forms, returning None for values in neither form
+fn parse_css(value: &str) -> Option<Result<Color, BaseError>> {
+    let value = value.trim();
+    if let Some(digits) = value.strip_prefix('#') {
+        return Some(parse_hex(digits).ok_or_else(parse_error));
+    }
+    let (name, args) = value.strip_suffix(')')?.split_once('(')?;
+    match name.trim() {
+        "rgb" | "rgba" => Some(parse_rgb(args).ok_or_else(parse_error)),
+        _ => None,
+    }
+}
+
+fn parse_hex(digits: &str) -> Option<Color> {
+    let nibbles = digits
+        .chars()
+        .map(|c| c.to_digit(16).map(|digit| digit as u8))
+        .collect::<Option<Vec<u8>>>()?;
+
+    // short forms repeat each digit, so #f80 is #ff8800
+    let bytes: Vec<u8> = match nibbles.len() {
+        3 | 4 => nibbles.iter().map(|nibble| nibble * 0x11).collect(),
+        6 | 8 => nibbles
+            .chunks(2)
+            .map(|pair| pair[0] << 4 | pair[1])
+            .collect(),
+        _ => return None,
+    };
+    Some(Color {
+        channels: [bytes[0], bytes[1], bytes[2]],
+        alpha: bytes.get(3).copied(),
+    })
+}
+
+fn parse_rgb(args: &str) -> Option<Color> {
+    let args: Vec<&str> = args.split(',').map(str::trim).collect();
+    if args.len() != 3 && args.len() != 4 {
+        return None;
+    }
+    let channels = args[..3]
+        .iter()
+        .map(|channel| channel.parse::<u8>().ok())
+        .collect::<Option<Vec<u8>>>()?;
+    let alpha = match args.get(3) {
+        Some(alpha) => {
+            let alpha = alpha
+                .parse::<f64>()
+                .ok()
+                .filter(|a| (0.0..=1.0).contains(a))?;
+            Some((alpha * 255.0).round() as u8)
+        }
+        None => None,
+    };
+    Some(Color {
+        channels: [channels[0], channels[1], channels[2]],
+        alpha,
+    })
+}
+
+fn from_packed(value: &Value) -> Result<Color, BaseError> {
+    let packed = value
+        .clone()
+        .into_inner()
+        .to_u32()
+        .ok_or(BaseError::OverflowError {
+            message: "Packed colors range from 0 to 0xffffffff",
+        })?;
+    let [a, b, c, d] = packed.to_be_bytes();
+    if packed > 0xffffff {
+        Ok(Color {
+            channels: [a, b, c],
+            alpha: Some(d),
+        })
+    } else {
+        Ok(Color {
+            channels: [b, c, d],
+            alpha: None,
+        })
+    }
+}
+
+/// Writes an alpha byte as a CSS fraction with at most three decimal places
+fn alpha_fraction(alpha: u8) -> String {
+    let fraction = format!("{:.3}", alpha as f64 / 255.0);
+    fraction
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
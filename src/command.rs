@@ -0,0 +1,334 @@
+use crate::errors::BaseError;
+use crate::opts::{Base, Operator, Opt};
+use crate::{
+    align, bit, color, compare, detect, diff, filter, info, interpret, math, mnemonic, net, op,
+    prime, repl, seq, sort, stats, text, unicode, uniq, units,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{file, serve, tui};
+use clap::Shell;
+use std::io;
+use structopt::StructOpt;
+
+/// Command enumerates the subcommands. Each is run with the options of changebase, given
+/// before or after it, which set how its results are written.
+#[derive(Clone, Debug, StructOpt)]
+pub enum Command {
+    /// Convert values between bases, as when no subcommand is given
+    Convert {
+        /// Values to convert. Use `-`, or pipe values in, to convert whitespace-separated values from stdin
+        values: Vec<String>,
+    },
+
+    /// Evaluate an expression, such as "0xff + 1 << 4", joining its words with spaces
+    Calc {
+        #[structopt(required = true)]
+        expression: Vec<String>,
+    },
+
+    /// Print a shell completion script for bash, zsh, fish, powershell or elvish
+    Completions {
+        #[structopt(possible_values = &Shell::variants(), case_insensitive = true)]
+        shell: Shell,
+    },
+
+    /// Start an interactive prompt. `_` or `last` refer to the previous result
+    Repl,
+
+    /// Answer convert, detect and info requests with JSON, over stdin and stdout or HTTP
+    Serve {
+        /// Read requests from stdin and write responses to stdout
+        #[structopt(long, conflicts_with = "http")]
+        stdio: bool,
+
+        /// Listen for HTTP requests on the address, such as 127.0.0.1:8080, answering
+        /// `/convert?value=0xff&to=dec` and `/all?value=0xff` with JSON
+        #[structopt(long, value_name = "address")]
+        http: Option<String>,
+    },
+
+    /// Show the value in every base as you type it
+    Tui,
+
+    /// Print the detected base of a value, failing if none can be detected
+    Detect { value: String },
+
+    /// Show the value decoded in every base it is valid in, printed in the output base or decimal
+    Interpret { value: String },
+
+    /// Encode text as UTF-8 bytes, or decode bytes back into text
+    Text {
+        #[structopt(subcommand)]
+        op: TextOp,
+    },
+
+    /// Show a code point, given as U+XXXX, the character or a number, with its UTF-8 and UTF-16 encodings
+    Char { value: String },
+
+    /// Show a color given as #rrggbb, rgb(r, g, b) or a packed integer in each of those forms
+    Color { value: String },
+
+    /// Print the netmask of a prefix length such as /24, or the prefix length of a netmask
+    Cidr {
+        value: String,
+
+        /// Use 128-bit IPv6 netmasks
+        #[structopt(long)]
+        ip6: bool,
+    },
+
+    /// Read a mnemonic phrase back into its value, using the BIP39 English words or --wordlist
+    Mnemonic { phrase: Vec<String> },
+
+    /// Round the value up to a multiple of the boundary, e.g. align 0x1234 0x1000
+    Align {
+        value: String,
+        boundary: String,
+
+        /// Round down instead of up
+        #[structopt(long)]
+        down: bool,
+    },
+
+    /// Copy stdin to stdout, converting the numbers in it, e.g. filter --to hex
+    Filter(FilterOpts),
+
+    /// Copy stdin to stdout, following each number in it with its conversion in parentheses
+    Annotate(FilterOpts),
+
+    /// Print the numbers read from stdin in numeric order, as written or in the output base if one is given
+    Sort {
+        /// Sort from largest to smallest
+        #[structopt(short, long)]
+        reverse: bool,
+    },
+
+    /// Print the count, minimum, maximum, exact sum and mean of the numbers read from stdin
+    Stats,
+
+    /// Print each distinct number read from stdin once, counting 0xff and 255 as the same number
+    Uniq {
+        /// Print the number of times each number was read before it
+        #[structopt(short, long)]
+        count: bool,
+    },
+
+    /// Compare two files of one number per line by value, printing the lines that differ and the numbers only in one file
+    Diff { a: String, b: String },
+
+    /// Compare two values, each read in its own base, printing their difference and XOR
+    Cmp { a: String, b: String },
+
+    /// Exit with 0 if a comparison such as "0xff == 255" holds, otherwise 1, printing the result only with -v
+    Check {
+        #[structopt(required = true)]
+        comparison: Vec<String>,
+    },
+
+    /// Show two values and their XOR in binary, listing the differing bits and the Hamming distance
+    Bitdiff { a: String, b: String },
+
+    /// Print the numbers from start to end, counting by step or 1, in the output base or the base of start
+    Seq {
+        start: String,
+        end: String,
+        step: Option<String>,
+    },
+
+    /// Convert an amount between wei, gwei and ether, e.g. units 1.5eth --to wei
+    Units {
+        value: String,
+
+        /// Print only the amount in this unit
+        #[structopt(long, alias = "o")]
+        to: Option<String>,
+
+        /// Convert between raw amounts and whole tokens with this many decimals, at most 255, as for ERC-20 tokens
+        #[structopt(long)]
+        decimals: Option<u32>,
+    },
+
+    /// Set, clear, toggle, test, extract or insert bits of a value
+    Bit {
+        #[structopt(subcommand)]
+        op: BitOp,
+    },
+
+    /// Print the prime factors of a value in the output base, or the base of the value
+    Factor { value: String },
+
+    /// Print yes if a value is prime, otherwise no, by the Miller-Rabin test
+    Isprime { value: String },
+
+    /// Print the gcd or lcm of two values, or the integer square root of one
+    Math {
+        #[structopt(subcommand)]
+        op: MathOp,
+    },
+
+    /// Print the mask with the bits HI:LO, or LO..HI exclusive, set
+    Mask { range: String },
+
+    /// Print the bit length, popcount and other properties of a value
+    Info { value: String },
+
+    /// Print the contents of a binary file as one big-endian integer, or one integer per word
+    File {
+        path: String,
+
+        /// Split the file into big-endian words of this many bytes, printing one per line
+        #[structopt(long)]
+        word: Option<usize>,
+    },
+
+    /// Apply a bitwise operator to two values, or one for `not`, or raise a base to an exponent modulo a third with `powmod`
+    Op {
+        #[structopt(possible_values = &Operator::variants(), case_insensitive = true)]
+        operator: Operator,
+        a: String,
+        b: Option<String>,
+        c: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug, StructOpt)]
+pub enum TextOp {
+    /// Print the UTF-8 bytes of the text in the output base, hex by default
+    Encode { text: String },
+
+    /// Print the text held by bytes given in hex, or binary with --ib, escaping non-printable characters
+    Decode { value: String },
+}
+
+#[derive(Clone, Debug, StructOpt)]
+pub enum MathOp {
+    /// Print the greatest common divisor of two values
+    Gcd { a: String, b: String },
+
+    /// Print the least common multiple of two values
+    Lcm { a: String, b: String },
+
+    /// Print the integer square root of a value, rounded down
+    Isqrt { value: String },
+}
+
+#[derive(Clone, Debug, StructOpt)]
+pub enum BitOp {
+    /// Set bit N of the value
+    Set { index: u64, value: String },
+
+    /// Clear bit N of the value
+    Clear { index: u64, value: String },
+
+    /// Flip bit N of the value
+    Toggle { index: u64, value: String },
+
+    /// Print 1 if bit N of the value is set, otherwise 0
+    Test { index: u64, value: String },
+
+    /// Extract bits HI:LO of the value, shifted down to bit zero
+    Extract { range: String, value: String },
+
+    /// Replace bits HI:LO of the value with the given field
+    Insert {
+        range: String,
+        field: String,
+        value: String,
+    },
+}
+
+#[derive(Clone, Debug, StructOpt)]
+pub struct FilterOpts {
+    /// Only convert numbers in this base, reading every word valid in it, with or without its prefix. Defaults to any prefixed number
+    #[structopt(long, possible_values = &Base::variants(), case_insensitive = true)]
+    pub from: Option<Base>,
+
+    /// Base to write the numbers in
+    #[structopt(long, possible_values = &Base::variants(), case_insensitive = true, default_value = "Dec")]
+    pub to: Base,
+
+    /// Regular expression matching the numbers to convert, or their first group
+    #[structopt(long)]
+    pub pattern: Option<String>,
+}
+
+impl Command {
+    /// Runs the subcommand, printing results to stdout
+    pub fn run(&self, opt: &Opt) -> Result<(), BaseError> {
+        match self {
+            Command::Convert { values } => convert(opt, values.clone()),
+            Command::Calc { expression } => convert(opt, vec![expression.join(" ")]),
+            Command::Completions { shell } => {
+                Opt::clap().gen_completions_to("changebase", *shell, &mut io::stdout());
+                Ok(())
+            }
+            Command::Repl => repl::run(opt),
+            #[cfg(not(target_arch = "wasm32"))]
+            Command::Serve { stdio: true, .. } => serve::run_stdio(opt),
+            #[cfg(not(target_arch = "wasm32"))]
+            Command::Serve {
+                http: Some(address),
+                ..
+            } => serve::run_http(opt, address),
+            #[cfg(not(target_arch = "wasm32"))]
+            Command::Serve { .. } => Err(BaseError::ArgError {
+                message: "serve needs --stdio or --http <address>",
+            }),
+            #[cfg(not(target_arch = "wasm32"))]
+            Command::Tui => tui::run(opt),
+            #[cfg(not(target_arch = "wasm32"))]
+            Command::File { path, word } => file::run(opt, path, *word),
+            // terminals, memory maps and sockets are not available to wasm
+            #[cfg(target_arch = "wasm32")]
+            Command::Serve { .. } | Command::Tui | Command::File { .. } => {
+                Err(BaseError::ArgError {
+                    message: "This subcommand is not available in the wasm build",
+                })
+            }
+            Command::Detect { value } => detect(opt, value),
+            Command::Interpret { value } => interpret(opt, value),
+            Command::Text { op } => text::run(opt, op),
+            Command::Char { value } => unicode::run(opt, value),
+            Command::Color { value } => color::run(opt, value),
+            Command::Cidr { value, ip6 } => net::run_cidr(opt, value, *ip6),
+            Command::Mnemonic { phrase } => mnemonic::decode(opt, phrase),
+            Command::Align {
+                value,
+                boundary,
+                down,
+            } => align::run(opt, value, boundary, *down),
+            Command::Bit { op } => bit::run(opt, op),
+            Command::Math { op } => math::run(opt, op),
+            Command::Factor { value } => prime::factor(opt, value),
+            Command::Isprime { value } => prime::isprime(opt, value),
+            Command::Units {
+                value,
+                to,
+                decimals,
+            } => units::run(opt, value, to.as_deref(), *decimals),
+            Command::Info { value } => info::run(opt, value),
+            Command::Filter(filter) => filter::run(opt, filter, false),
+            Command::Annotate(filter) => filter::run(opt, filter, true),
+            Command::Sort { reverse } => sort::run(opt, *reverse),
+            Command::Stats => stats::run(opt),
+            Command::Uniq { count } => uniq::run(opt, *count),
+            Command::Diff { a, b } => diff::run(opt, a, b),
+            Command::Cmp { a, b } => compare::cmp(opt, a, b),
+            Command::Bitdiff { a, b } => compare::bitdiff(opt, a, b),
+            Command::Seq { start, end, step } => seq::run(opt, start, end, step.as_deref()),
+            Command::Check { comparison } => compare::check(opt, &comparison.join(" ")),
+            Command::Mask { range } => bit::run_mask(opt, range),
+            Command::Op { operator, a, b, c } => {
+                op::run(opt, operator, a, b.as_deref(), c.as_deref())
+            }
+        }
+    }
+}
+
+/// Converts the values as if they were given without a subcommand
+fn convert(opt: &Opt, values: Vec<String>) -> Result<(), BaseError> {
+    let mut opt = opt.clone();
+    opt.cmd = None;
+    opt.values = values;
+    crate::run(&opt)
+}
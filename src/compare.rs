@@ -0,0 +1,180 @@
+use crate::base::Value;
+use crate::errors::BaseError;
+use crate::json;
+use crate::opts::{Base, Opt};
+use crate::{format_integer, info, parse_number};
+use num::bigint::BigInt;
+use std::cmp::Ordering;
+use std::io::{self, IsTerminal};
+
+const HIGHLIGHT: &str = "\x1b[7m";
+const RESET: &str = "\x1b[0m";
+
+/// Reads each value in its own base, so 0x1f4 and 500 can be compared
+fn read(opt: &Opt, value: &str) -> Result<BigInt, BaseError> {
+    read_parsed(opt, value).map(|(num, _)| num.into_inner())
+}
+
+/// Reads the value as an integer, with the width its digits were written in, if any
+fn read_parsed(opt: &Opt, value: &str) -> Result<(Value, Option<usize>), BaseError> {
+    parse_number(opt, value, None)
+        .and_then(|parsed| Ok((parsed.integer()?.clone(), parsed.width)))
+        .map_err(|e| e.in_input(value))
+}
+
+/// Prints how the values compare, then their difference `a - b` and their XOR in the
+/// output base, or decimal
+pub fn cmp(opt: &Opt, a: &str, b: &str) -> Result<(), BaseError> {
+    let (lhs, rhs) = (read(opt, a)?, read(opt, b)?);
+    let output = opt.get_output().unwrap_or(Base::Dec);
+    let format = |num: BigInt| format_integer(opt, Value::new(num), output.clone());
+    let (operator, order) = match lhs.cmp(&rhs) {
+        Ordering::Less => ("<", "less"),
+        Ordering::Equal => ("==", "equal"),
+        Ordering::Greater => (">", "greater"),
+    };
+    let difference = format(&lhs - &rhs)?;
+    let xor = format(&lhs ^ &rhs)?;
+
+    if opt.json {
+        let fields = [
+            ("order", order.to_string()),
+            ("difference", difference),
+            ("xor", xor),
+        ];
+        println!("{}", json::object(&fields));
+    } else {
+        println!("{} {} {}", a, operator, b);
+        println!("difference: {}", difference);
+        println!("xor: {}", xor);
+    }
+    Ok(())
+}
+
+/// Checks a comparison such as `0xff == 255`, with `==`, `!=`, `<`, `<=`, `>` or `>=`
+/// between two values or expressions, printing whether it holds only if verbose.
+/// Returns `BaseError::CheckFailed` if it does not, so the exit code can be tested.
+pub fn check(opt: &Opt, comparison: &str) -> Result<(), BaseError> {
+    let (a, operator, b) = split_comparison(comparison).ok_or(BaseError::ArgError {
+        message: "check takes a comparison such as 0xff == 255",
+    })?;
+    // the detected bases are only reported when verbose, like the result
+    let mut quiet = opt.clone();
+    quiet.quiet |= opt.verbose == 0;
+    let order = read(&quiet, a)?.cmp(&read(&quiet, b)?);
+    let holds = match operator {
+        "==" => order == Ordering::Equal,
+        "!=" => order != Ordering::Equal,
+        "<" => order == Ordering::Less,
+        "<=" => order != Ordering::Greater,
+        ">" => order == Ordering::Greater,
+        _ => order != Ordering::Less,
+    };
+
+    if opt.verbose > 0 {
+        println!("{}: {}", comparison.trim(), holds);
+    }
+    if holds {
+        Ok(())
+    } else {
+        Err(BaseError::CheckFailed)
+    }
+}
+
+/// Splits the comparison around its one comparison operator, leaving the shifts `<<`
+/// and `>>` of expressions alone
+fn split_comparison(comparison: &str) -> Option<(&str, &str, &str)> {
+    let bytes = comparison.as_bytes();
+    let mut found = None;
+    let mut index = 0;
+    while index < bytes.len() {
+        let rest = &comparison[index..];
+        let operator = if rest.starts_with("<<") || rest.starts_with(">>") {
+            index += 2;
+            continue;
+        } else if ["==", "!=", "<=", ">="]
+            .iter()
+            .any(|op| rest.starts_with(op))
+        {
+            &rest[..2]
+        } else if rest.starts_with(['<', '>']) {
+            &rest[..1]
+        } else {
+            index += 1;
+            continue;
+        };
+        if found.is_some() {
+            return None;
+        }
+        found = Some((index, operator));
+        index += operator.len();
+    }
+
+    let (index, operator) = found?;
+    let (a, b) = (&comparison[..index], &comparison[index + operator.len()..]);
+    if a.trim().is_empty() || b.trim().is_empty() {
+        return None;
+    }
+    Some((a.trim(), operator, b.trim()))
+}
+
+/// Prints both values and their XOR in binary, with the differing bits highlighted on
+/// a terminal, then the positions of those bits and their count, the Hamming distance.
+/// Negative values are compared as two's complement of the --width.
+pub fn bitdiff(opt: &Opt, a: &str, b: &str) -> Result<(), BaseError> {
+    let (lhs, lhs_width) = read_parsed(opt, a)?;
+    let (rhs, rhs_width) = read_parsed(opt, b)?;
+    let width = opt.width.or(lhs_width.max(rhs_width));
+    let lhs = info::unsigned(&lhs, width)?.into_inner();
+    let rhs = info::unsigned(&rhs, width)?.into_inner();
+    let xor = &lhs ^ &rhs;
+    // without a width, show the whole number of bytes holding the larger value
+    let width =
+        width.unwrap_or_else(|| (lhs.bits().max(rhs.bits()).max(1) as usize).div_ceil(8) * 8);
+
+    let positions: Vec<String> = (0..width as u64)
+        .filter(|index| xor.bit(*index))
+        .map(|index| index.to_string())
+        .collect();
+    let positions = if positions.is_empty() {
+        "none".to_string()
+    } else {
+        positions.join(", ")
+    };
+    let distance = xor.iter_u64_digits().map(u64::count_ones).sum::<u32>();
+
+    if opt.json {
+        let fields = [
+            ("xor", bits(&xor, &xor, width, false).replace(' ', "")),
+            ("differing_bits", positions),
+            ("hamming_distance", distance.to_string()),
+        ];
+        println!("{}", json::object(&fields));
+        return Ok(());
+    }
+    let highlight = io::stdout().is_terminal();
+    println!("a:   {}", bits(&lhs, &xor, width, highlight));
+    println!("b:   {}", bits(&rhs, &xor, width, highlight));
+    println!("xor: {}", bits(&xor, &xor, width, highlight));
+    println!("differing bits: {}", positions);
+    println!("hamming distance: {}", distance);
+    Ok(())
+}
+
+/// Writes the low `width` bits of the number in nibbles, most significant first,
+/// highlighting the bits set in `diff`
+fn bits(num: &BigInt, diff: &BigInt, width: usize, highlight: bool) -> String {
+    let mut digits = String::new();
+    for index in (0..width as u64).rev() {
+        let bit = if num.bit(index) { '1' } else { '0' };
+        if highlight && diff.bit(index) {
+            digits.push_str(&format!("{}{}{}", HIGHLIGHT, bit, RESET));
+        } else {
+            digits.push(bit);
+        }
+        if index > 0 && index.is_multiple_of(4) {
+            digits.push(' ');
+        }
+    }
+    digits
+}
@@ -0,0 +1,95 @@
+use crate::defs;
+use crate::errors::BaseError;
+use crate::opts::{Base, ColorWhen, Opt};
+use std::env;
+use std::path::PathBuf;
+
+/// Returns the path of the config file, under $XDG_CONFIG_HOME or ~/.config
+fn path() -> Option<PathBuf> {
+    let dir = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(dir.join("changebase").join("config.toml"))
+}
+
+/// Arguments that choose the output base
+const OUTPUT_ARGS: &[&str] = &[
+    "output",
+    "binary-output",
+    "octal-output",
+    "decimal-output",
+    "hex-output",
+];
+
+/// Arguments that choose how bases are detected
+const DETECT_ARGS: &[&str] = &["detect-strict", "detect-prefer", "detect-legacy"];
+
+/// Fills in options not given on the command line from the config file, if there is one.
+/// The file holds top-level keys such as `output = "hex"`, `upper = true`, `pad = 8`,
+/// `group = 4`, `detect = "strict"` and `color = "never"`.
+pub fn apply(opt: &mut Opt) -> Result<(), BaseError> {
+    let path = match path().filter(|path| path.is_file()) {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let tables = defs::load(&path.to_string_lossy())?;
+    let entries = tables
+        .into_iter()
+        .filter(|table| table.name.is_empty())
+        .flat_map(|table| table.entries);
+
+    for (key, value) in entries {
+        match key.as_str() {
+            "output" if !opt.given(OUTPUT_ARGS) => opt.output = Some(parse_base(&value)?),
+            "upper" if !opt.given(&["upper"]) => opt.upper = parse_bool(&value)?,
+            "pad" if !opt.given(&["pad"]) => opt.pad = Some(parse_count(&value)?),
+            "group" if !opt.given(&["group"]) => {
+                opt.group = match value.as_str() {
+                    "true" => Some(None),
+                    "false" => None,
+                    value => Some(Some(parse_count(value)?)),
+                }
+            }
+            "detect" if !opt.given(DETECT_ARGS) => match value.as_str() {
+                "default" => {}
+                "strict" => opt.detect_strict = true,
+                "legacy" => opt.detect_legacy = true,
+                base => opt.detect_prefer = Some(parse_base(base)?),
+            },
+            "color" if !opt.given(&["color"]) => {
+                opt.color = value
+                    .parse::<ColorWhen>()
+                    .map_err(|_| BaseError::ArgError {
+                        message: "Config file color must be auto, always or never",
+                    })?
+            }
+            "output" | "upper" | "pad" | "group" | "detect" | "color" => {}
+            _ => {
+                return Err(BaseError::ArgError {
+                    message:
+                        "Unknown key in config file, use output, upper, pad, group, detect or color",
+                })
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_base(value: &str) -> Result<Base, BaseError> {
+    value.parse::<Base>().map_err(|_| BaseError::ArgError {
+        message: "Config file names an unknown base",
+    })
+}
+
+fn parse_bool(value: &str) -> Result<bool, BaseError> {
+    value.parse::<bool>().map_err(|_| BaseError::ArgError {
+        message: "Config file flags must be true or false",
+    })
+}
+
+fn parse_count(value: &str) -> Result<usize, BaseError> {
+    value.parse::<usize>().map_err(|_| BaseError::ArgError {
+        message: "Config file sizes must be whole numbers",
+    })
+}
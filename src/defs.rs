@@ -0,0 +1,366 @@
+use crate::errors::{BaseError, Syntax};
+use std::fs;
+
+/// Table is a named group of definitions read from a file, in file order
+pub struct Table {
+    pub name: String,
+    pub entries: Vec<(String, String)>,
+}
+
+/// Reads named tables of definitions from a JSON file, or TOML for any other extension.
+/// Only string and integer values are supported. Top-level entries are placed in a
+/// table with an empty name.
+pub fn load(path: &str) -> Result<Vec<Table>, BaseError> {
+    let text = fs::read_to_string(path).map_err(|_| BaseError::IoError {
+        message: "Unable to read definition file",
+    })?;
+    let tables = if path.ends_with(".json") {
+        parse_json(&text)
+    } else {
+        parse_toml(&text)
+    };
+    tables.ok_or(BaseError::InvalidSyntax {
+        syntax: Syntax::Definitions,
+    })
+}
+
+/// Loads the table given after `#` in `spec`, e.g. `regs.toml#STATUS`. Without a
+/// table name, the file must hold a single table.
+pub fn load_table(spec: &str) -> Result<Table, BaseError> {
+    let (path, name) = match spec.rsplit_once('#') {
+        Some((path, name)) => (path, Some(name)),
+        None => (spec, None),
+    };
+
+    let mut tables = load(path)?;
+    tables.retain(|table| !table.entries.is_empty());
+    match name {
+        Some(name) => tables.into_iter().find(|table| table.name == name),
+        None if tables.len() == 1 => tables.pop(),
+        None => None,
+    }
+    .ok_or(BaseError::ArgError {
+        message: "Definition table not found, name it with file#TABLE",
+    })
+}
+
+fn table<'a>(tables: &'a mut Vec<Table>, name: &str) -> &'a mut Table {
+    match tables.iter().position(|table| table.name == name) {
+        Some(index) => &mut tables[index],
+        None => {
+            tables.push(Table {
+                name: name.to_string(),
+                entries: Vec::new(),
+            });
+            tables.last_mut().unwrap()
+        }
+    }
+}
+
+/// Parses `[table]` headers and `key = value` lines
+fn parse_toml(text: &str) -> Option<Vec<Table>> {
+    let mut tables = Vec::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name.strip_suffix(']')?.trim();
+            if name.starts_with('[') {
+                return None;
+            }
+            current = unquote(name)?;
+            table(&mut tables, &current);
+            continue;
+        }
+
+        let (key, value) = line.split_once('=')?;
+        let entry = (unquote(key.trim())?, unquote(value.trim())?);
+        table(&mut tables, &current).entries.push(entry);
+    }
+    Some(tables)
+}
+
+/// Drops a `#` comment that is not inside a string
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            _ if escaped => escaped = false,
+            (Some('"'), '\\') => escaped = true,
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '#') => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Strips the quotes from a TOML string or key, leaving bare words and integers as is.
+/// Double-quoted strings may hold escapes; single-quoted strings are literal.
+fn unquote(value: &str) -> Option<String> {
+    if let Some(inner) = value.strip_prefix('\'') {
+        return inner
+            .strip_suffix('\'')
+            .filter(|inner| !inner.contains('\''))
+            .map(str::to_string);
+    }
+    if let Some(inner) = value.strip_prefix('"') {
+        let mut chars = inner.chars();
+        let mut result = String::new();
+        loop {
+            match chars.next()? {
+                '"' => return chars.next().is_none().then_some(result),
+                '\\' => result.push(match chars.next()? {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    c @ ('"' | '\\') => c,
+                    _ => return None,
+                }),
+                c => result.push(c),
+            }
+        }
+    }
+    if value.is_empty() || value.contains(char::is_whitespace) {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// Parses an object whose values are either scalars or objects of scalars
+fn parse_json(text: &str) -> Option<Vec<Table>> {
+    let mut parser = JsonParser {
+        chars: text.chars().collect(),
+        pos: 0,
+    };
+    let mut tables = Vec::new();
+    parser.expect('{')?;
+    parser.members(|parser, key| {
+        if parser.peek()? == '{' {
+            parser.expect('{')?;
+            table(&mut tables, &key);
+            parser.members(|parser, entry| {
+                let value = parser.scalar()?;
+                table(&mut tables, &key).entries.push((entry, value));
+                Some(())
+            })
+        } else {
+            let value = parser.scalar()?;
+            table(&mut tables, "").entries.push((key, value));
+            Some(())
+        }
+    })?;
+    parser.skip_whitespace();
+    if parser.pos == parser.chars.len() {
+        Some(tables)
+    } else {
+        None
+    }
+}
+
+struct JsonParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl JsonParser {
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: char) -> Option<()> {
+        if self.peek()? == c {
+            self.pos += 1;
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// Parses `"key": value` members up to the closing brace of an object
+    fn members<F>(&mut self, mut member: F) -> Option<()>
+    where
+        F: FnMut(&mut JsonParser, String) -> Option<()>,
+    {
+        if self.peek()? == '}' {
+            self.pos += 1;
+            return Some(());
+        }
+        loop {
+            let key = self.string()?;
+            self.expect(':')?;
+            member(self, key)?;
+            match self.peek()? {
+                ',' => self.pos += 1,
+                '}' => {
+                    self.pos += 1;
+                    return Some(());
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn scalar(&mut self) -> Option<String> {
+        if self.peek()? == '"' {
+            return self.string();
+        }
+        let start = self.pos;
+        if self.chars[self.pos] == '-' {
+            self.pos += 1;
+        }
+        let digits = self.pos;
+        while self.pos < self.chars.len() && self.chars[self.pos].is_ascii_alphanumeric() {
+            self.pos += 1;
+        }
+        if digits == self.pos {
+            None
+        } else {
+            Some(self.chars[start..self.pos].iter().collect())
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.expect('"')?;
+        let mut result = String::new();
+        loop {
+            let c = *self.chars.get(self.pos)?;
+            self.pos += 1;
+            match c {
+                '"' => return Some(result),
+                '\\' => {
+                    let escaped = *self.chars.get(self.pos)?;
+                    self.pos += 1;
+                    result.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        c => c,
+                    });
+                }
+                c => result.push(c),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(table: &Table) -> Vec<(&str, &str)> {
+        table
+            .entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_toml_tables() {
+        let tables = parse_toml(
+            "top = 1\n# a comment\n[STATUS]\nREADY = 0x1 # trailing\n\"BUSY\" = '1 << 1'\n\n[MODE]\nRUN = -3\n",
+        )
+        .unwrap();
+        let names: Vec<&str> = tables.iter().map(|table| table.name.as_str()).collect();
+        assert_eq!(names, ["", "STATUS", "MODE"]);
+        assert_eq!(entries(&tables[0]), [("top", "1")]);
+        assert_eq!(entries(&tables[1]), [("READY", "0x1"), ("BUSY", "1 << 1")]);
+        assert_eq!(entries(&tables[2]), [("RUN", "-3")]);
+    }
+
+    #[test]
+    fn merges_repeated_toml_tables() {
+        let tables = parse_toml("[A]\nX = 1\n[B]\nY = 2\n[A]\nZ = 3\n").unwrap();
+        assert_eq!(tables.len(), 2);
+        assert_eq!(entries(&tables[0]), [("X", "1"), ("Z", "3")]);
+    }
+
+    #[test]
+    fn unescapes_toml_strings() {
+        let tables = parse_toml(
+            r#"a = "say \"hi\" # not a comment"
+b = "tab\there\\"
+c = 'C:\path'
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            entries(&tables[0]),
+            [
+                ("a", "say \"hi\" # not a comment"),
+                ("b", "tab\there\\"),
+                ("c", "C:\\path"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_toml() {
+        for text in [
+            "key\n",
+            "key = \"open\n",
+            "key = \"bad \\q escape\"\n",
+            "key = \"a\" \"b\"\n",
+            "two words = 1\n",
+            "key = \n",
+            "[open\n",
+            "[[array]]\n",
+        ] {
+            assert!(parse_toml(text).is_none(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn parses_json_tables() {
+        let tables = parse_json(
+            r#"{"top": 1, "STATUS": {"READY": "0x1", "BUSY": 2}, "MODE": {"RUN": -3}, "EMPTY": {}}"#,
+        )
+        .unwrap();
+        let names: Vec<&str> = tables.iter().map(|table| table.name.as_str()).collect();
+        assert_eq!(names, ["", "STATUS", "MODE", "EMPTY"]);
+        assert_eq!(entries(&tables[1]), [("READY", "0x1"), ("BUSY", "2")]);
+        assert_eq!(entries(&tables[2]), [("RUN", "-3")]);
+        assert!(tables[3].entries.is_empty());
+    }
+
+    #[test]
+    fn unescapes_json_strings() {
+        let tables = parse_json(r#"{"a\"b": "line\nbreak", "c": "back\\slash"}"#).unwrap();
+        assert_eq!(
+            entries(&tables[0]),
+            [("a\"b", "line\nbreak"), ("c", "back\\slash")]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_json() {
+        for text in [
+            "",
+            "[]",
+            "{",
+            r#"{"a": 1,}"#,
+            r#"{"a" 1}"#,
+            r#"{"a": -}"#,
+            r#"{"a": "open}"#,
+            r#"{"a": {"b": {"c": 1}}}"#,
+            r#"{"a": 1} trailing"#,
+        ] {
+            assert!(parse_json(text).is_none(), "{:?}", text);
+        }
+    }
+}
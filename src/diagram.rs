@@ -0,0 +1,49 @@
+use crate::base::Value;
+
+/// Number of bits shown on each row of a diagram
+const ROW_BITS: usize = 16;
+
+const HIGHLIGHT: &str = "\x1b[7m";
+const RESET: &str = "\x1b[0m";
+
+/// Renders the low `width` bits of an unsigned value as a ruler of bit indices above
+/// the bits, most significant first. Nibbles are separated by a space and bytes by `|`.
+/// Set bits are shown in reverse video when `highlight` is true.
+pub fn render(num: &Value, width: usize, highlight: bool) -> String {
+    let value = num.clone().into_inner();
+    let rows = width.div_ceil(ROW_BITS);
+
+    (0..rows)
+        .rev()
+        .map(|row| {
+            let low = row * ROW_BITS;
+            let high = (low + ROW_BITS).min(width);
+            let mut indices = String::new();
+            let mut bits = String::new();
+            for index in (low..high).rev() {
+                if index + 1 != high {
+                    let separator = if (index + 1).is_multiple_of(8) {
+                        " |"
+                    } else if (index + 1).is_multiple_of(4) {
+                        "  "
+                    } else {
+                        ""
+                    };
+                    indices.push_str(separator);
+                    bits.push_str(separator);
+                }
+
+                indices.push_str(&format!("{:>3}", index));
+                let set = value.bit(index as u64);
+                let bit = if set { "1" } else { "0" };
+                if set && highlight {
+                    bits.push_str(&format!("  {}{}{}", HIGHLIGHT, bit, RESET));
+                } else {
+                    bits.push_str(&format!("  {}", bit));
+                }
+            }
+            format!("{}\n{}", indices, bits)
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
@@ -0,0 +1,108 @@
+use crate::base::Value;
+use crate::errors::BaseError;
+use crate::opts::Opt;
+use crate::{format_integer, line_values, open_file, parse_number};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufRead, BufWriter, Write};
+
+/// A number read from a line of a file, with its line number and the value as written
+struct Line {
+    number: usize,
+    value: String,
+    num: Value,
+}
+
+/// Compares two files of one number per line, so a dump in hex can be checked against a
+/// list in decimal. Prints each line whose numbers differ, then the other numbers found
+/// in only one of the files, as written or in the output base if one is given. Returns
+/// `BaseError::Differs` if anything was printed.
+pub fn run(opt: &Opt, a: &str, b: &str) -> Result<(), BaseError> {
+    let (lines_a, lines_b) = (read_lines(opt, a)?, read_lines(opt, b)?);
+    let output = opt.get_output();
+    let format = |line: &Line| match &output {
+        Some(base) => format_integer(opt, line.num.clone(), base.clone()),
+        None => Ok(line.value.clone()),
+    };
+
+    let mut differences = Vec::new();
+    let mut changed = HashSet::new();
+    let by_line: HashMap<usize, &Line> = lines_b.iter().map(|line| (line.number, line)).collect();
+    for line in &lines_a {
+        if let Some(other) = by_line
+            .get(&line.number)
+            .filter(|other| other.num != line.num)
+        {
+            changed.insert(line.number);
+            differences.push(format!(
+                "line {}: {} != {}",
+                line.number,
+                format(line)?,
+                format(other)?
+            ));
+        }
+    }
+    // numbers on lines already shown as differing are not repeated
+    for (path, lines, other) in &[(a, &lines_a, &lines_b), (b, &lines_b, &lines_a)] {
+        for line in only_in(lines, other) {
+            if !changed.contains(&line.number) {
+                differences.push(format!("only in {}: {}", path, format(line)?));
+            }
+        }
+    }
+
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    for difference in &differences {
+        writeln!(writer, "{}", difference).map_err(|_| BaseError::IoError {
+            message: "Unable to write to stdout",
+        })?;
+    }
+    writer.flush().map_err(|_| BaseError::IoError {
+        message: "Unable to write to stdout",
+    })?;
+    if differences.is_empty() {
+        Ok(())
+    } else {
+        Err(BaseError::Differs)
+    }
+}
+
+/// Reads the number on each non-empty line of the file
+fn read_lines(opt: &Opt, path: &str) -> Result<Vec<Line>, BaseError> {
+    let mut lines = Vec::new();
+    for (index, line) in open_file(path)?.lines().enumerate() {
+        let line = line.map_err(|_| BaseError::IoError {
+            message: "Unable to read input file",
+        })?;
+        for value in line_values(&line, true) {
+            let num = parse_number(opt, value, None)
+                .and_then(|parsed| parsed.integer().cloned())
+                .map_err(|e| e.in_input(value))?;
+            lines.push(Line {
+                number: index + 1,
+                value: value.to_string(),
+                num,
+            });
+        }
+    }
+    Ok(lines)
+}
+
+/// Returns the lines whose numbers are not in `other`, counting repeats, so a number
+/// read twice in one file and once in the other is left over once
+fn only_in<'a>(lines: &'a [Line], other: &[Line]) -> Vec<&'a Line> {
+    let mut counts: HashMap<&Value, usize> = HashMap::new();
+    for line in other {
+        *counts.entry(&line.num).or_insert(0) += 1;
+    }
+    lines
+        .iter()
+        .filter(|line| match counts.get_mut(&line.num) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect()
+}
@@ -1,13 +1,176 @@
+use crate::opts::Base;
 use thiserror::Error;
 
 /// BaseError enumerates all possible errors returned by this library.
 #[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum BaseError {
-    /// Represents a failure to parse the input value
-    #[error("Unable to parse input value")]
-    ParseError { message: &'static str },
+    /// Represents an empty value, or a prefix or suffix with no digits after it
+    #[error("no digits given")]
+    EmptyInput,
+
+    /// Represents a character that is not a digit of the base, at its character index in the value
+    #[error("digit '{digit}' is not valid in {}", base.repr().to_lowercase())]
+    InvalidDigit {
+        digit: char,
+        position: usize,
+        base: Base,
+    },
+
+    /// Represents a base prefix, such as 0x, that does not match the input base
+    #[error("prefix {prefix} is not valid in {}", base.repr().to_lowercase())]
+    InvalidPrefix { prefix: String, base: Base },
+
+    /// Represents a value whose base can not be detected without a prefix or suffix
+    #[error("unable to detect the base of a value with no base prefix or suffix")]
+    AmbiguousBase,
+
+    /// Represents a value that is not valid in any of the bases it was tried in
+    #[error("value is not valid in any base")]
+    NoValidBase,
+
+    /// Represents an unprefixed value whose base can not be detected, as the digit at
+    /// this character index is valid in none of the bases tried
+    #[error("digit '{digit}' is not valid in any base")]
+    UndetectedDigit { digit: char, position: usize },
+
+    /// Represents a value not written in the form of its base, for bases that are not
+    /// read digit by digit, such as roman or ip4
+    #[error("{}", base.format_hint())]
+    InvalidFormat { base: Base },
+
+    /// Represents input that is not a value in a base, such as a float, an amount or a
+    /// definition file, that is not written as it should be
+    #[error("{}", syntax.hint())]
+    InvalidSyntax { syntax: Syntax },
+
+    /// Represents a mixed case Ethereum address whose EIP-55 checksum does not match
+    #[error("the EIP-55 checksum of the address does not match")]
+    InvalidChecksum,
+
+    /// Represents an invalid digit in a given value, keeping the value to point at the digit
+    #[error("{error}")]
+    Input {
+        input: String,
+        error: Box<BaseError>,
+    },
 
     /// Represents an invalid argument
     #[error("Invalid Arguments")]
     ArgError { message: &'static str },
+
+    /// Represents a value that does not fit in the requested width
+    #[error("Value out of range")]
+    OverflowError { message: &'static str },
+
+    /// Represents a value that does not fit in a primitive integer type
+    #[error("Value requires {bits_required} bits")]
+    Overflow { bits_required: u64 },
+
+    /// Represents a failure to evaluate an expression
+    #[error("Unable to evaluate expression")]
+    EvalError { message: &'static str },
+
+    /// Represents values of a batch that failed to convert with --keep-going, each of
+    /// which has already been reported
+    #[error("{failed} of {total} values could not be converted")]
+    BatchFailure { failed: usize, total: usize },
+
+    /// Represents numbers that were compared and found to differ, each difference having
+    /// already been printed
+    #[error("the numbers differ")]
+    Differs,
+
+    /// Represents a comparison given to check that does not hold
+    #[error("the comparison is false")]
+    CheckFailed,
+
+    /// Represents a failure to read input or write output
+    #[error("I/O error")]
+    IoError { message: &'static str },
+}
+
+/// Syntax names the kinds of input, other than values in a base, that can be malformed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Syntax {
+    Float,
+    FloatBits,
+    Fraction,
+    Exponent,
+    Text,
+    TextBytes,
+    Character,
+    Color,
+    Units,
+    Mnemonic,
+    Verilog,
+    Definitions,
+}
+
+impl Syntax {
+    /// Describes how input of this kind is written
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Syntax::Float => "Float: only enter a decimal floating point number, e.g. 3.5 or -1e10",
+            Syntax::FloatBits => "Float: only enter a bit pattern valid in the input base",
+            Syntax::Fraction => "Fraction: only enter valid digits around a single radix point",
+            Syntax::Exponent => "Exponent: only enter a decimal power of two",
+            Syntax::Text => "Text: only enter digits valid in the input base",
+            Syntax::TextBytes => "Text: enter a whole number of bytes",
+            Syntax::Character => "Character: enter U+XXXX, a single character or a code point",
+            Syntax::Color => {
+                "Color: only enter #rgb, #rrggbb, #rrggbbaa, rgb(r, g, b), rgba(r, g, b, a) or an integer"
+            }
+            Syntax::Units => {
+                "Units: only enter a decimal amount with at most the unit's decimals, e.g. 1.5eth"
+            }
+            Syntax::Mnemonic => "Mnemonic: word is not in the wordlist",
+            Syntax::Verilog => "Verilog: write literals as <width>'<b|o|d|h><digits>, e.g. 8'hff",
+            Syntax::Definitions => "Unable to parse definition file",
+        }
+    }
+}
+
+impl BaseError {
+    /// Returns the process exit code for the error, as listed in the help text
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            BaseError::ArgError { .. }
+            | BaseError::IoError { .. }
+            | BaseError::Differs
+            | BaseError::CheckFailed => 1,
+            BaseError::EmptyInput
+            | BaseError::InvalidDigit { .. }
+            | BaseError::InvalidPrefix { .. }
+            | BaseError::InvalidFormat { .. }
+            | BaseError::InvalidSyntax { .. }
+            | BaseError::InvalidChecksum
+            | BaseError::EvalError { .. } => 2,
+            BaseError::AmbiguousBase
+            | BaseError::NoValidBase
+            | BaseError::UndetectedDigit { .. } => 3,
+            BaseError::OverflowError { .. } | BaseError::Overflow { .. } => 4,
+            BaseError::BatchFailure { .. } => 5,
+            BaseError::Input { error, .. } => error.exit_code(),
+        }
+    }
+
+    /// Attaches the value an invalid digit was found in, if the digit's position points
+    /// into it, so the error can be shown under the digit
+    pub fn in_input(self, input: &str) -> BaseError {
+        match self {
+            BaseError::InvalidDigit {
+                digit, position, ..
+            }
+            | BaseError::UndetectedDigit { digit, position }
+                if input.chars().nth(position) == Some(digit) =>
+            {
+                BaseError::Input {
+                    input: input.to_string(),
+                    error: Box::new(self),
+                }
+            }
+            error => error,
+        }
+    }
 }
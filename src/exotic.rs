@@ -0,0 +1,369 @@
+use crate::errors::BaseError;
+use crate::opts::DozenalDigits;
+use num::{bigint::BigInt, Integer, Signed, ToPrimitive, Zero};
+
+/// Writes the value in balanced ternary, using the digits T (minus one), 0 and 1
+pub fn to_balanced_ternary(value: &BigInt) -> String {
+    let three = BigInt::from(3);
+    let mut value = value.clone();
+    let mut digits = Vec::new();
+    while !value.is_zero() {
+        let remainder = value.mod_floor(&three);
+        if remainder == BigInt::from(2) {
+            digits.push('T');
+            value = (value + 1) / &three;
+        } else {
+            digits.push(if remainder.is_zero() { '0' } else { '1' });
+            value = (value - remainder) / &three;
+        }
+    }
+    finish(digits)
+}
+
+/// Reads balanced ternary digits T, 0 and 1, in either case
+pub fn parse_balanced_ternary(value: &str) -> Option<BigInt> {
+    let digits = value
+        .chars()
+        .map(|c| match c {
+            'T' | 't' => Some(-1),
+            '0' => Some(0),
+            '1' => Some(1),
+            _ => None,
+        })
+        .collect::<Option<Vec<i32>>>();
+    accumulate(digits?, 3)
+}
+
+/// Writes the value in base -2, which needs no sign for negative values
+pub fn to_negabinary(value: &BigInt) -> String {
+    let two = BigInt::from(2);
+    let mut value = value.clone();
+    let mut digits = Vec::new();
+    while !value.is_zero() {
+        let remainder = value.mod_floor(&two);
+        digits.push(if remainder.is_zero() { '0' } else { '1' });
+        value = (value - remainder) / -&two;
+    }
+    finish(digits)
+}
+
+/// Reads base -2 digits 0 and 1
+pub fn parse_negabinary(value: &str) -> Option<BigInt> {
+    let digits = value
+        .chars()
+        .map(|c| c.to_digit(2).map(|digit| digit as i32))
+        .collect::<Option<Vec<i32>>>();
+    accumulate(digits?, -2)
+}
+
+/// Reverses digits collected least significant first, writing zero as "0"
+fn finish(digits: Vec<char>) -> String {
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+    digits.into_iter().rev().collect()
+}
+
+/// Sums the digits, most significant first, in the given radix
+fn accumulate(digits: Vec<i32>, radix: i32) -> Option<BigInt> {
+    if digits.is_empty() {
+        return None;
+    }
+    Some(
+        digits
+            .into_iter()
+            .fold(BigInt::zero(), |total, digit| total * radix + digit),
+    )
+}
+
+/// Writes the value as a spreadsheet column, in bijective base 26 where A is 1, Z is 26
+/// and AA is 27
+pub fn to_column(value: &BigInt) -> Result<String, BaseError> {
+    if !value.is_positive() {
+        return Err(BaseError::OverflowError {
+            message: "Spreadsheet columns start at 1",
+        });
+    }
+
+    let radix = BigInt::from(26);
+    let mut value = value.clone();
+    let mut letters = Vec::new();
+    while !value.is_zero() {
+        value -= 1;
+        let (quotient, remainder) = value.div_rem(&radix);
+        letters.push((b'A' + remainder.to_u8().unwrap()) as char);
+        value = quotient;
+    }
+    Ok(letters.into_iter().rev().collect())
+}
+
+/// Reads spreadsheet column letters in either case
+pub fn parse_column(value: &str) -> Option<BigInt> {
+    let digits = value
+        .chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => Some(c as i32 - 'A' as i32 + 1),
+            _ => None,
+        })
+        .collect::<Option<Vec<i32>>>();
+    accumulate(digits?, 26)
+}
+
+/// Seconds per minute and minutes per hour, the radices of `H:MM:SS`
+pub const HMS: [u32; 2] = [60, 60];
+
+/// Writes the value in a mixed radix, most significant radix first, with the fields
+/// separated by colons. The leading field is unbounded, so 90061 with radices 24, 60
+/// and 60 is `1:01:01:01`. Bounded fields are padded to the width of their largest digit.
+pub fn to_mixed_radix(value: &BigInt, radices: &[u32]) -> String {
+    let mut magnitude = value.abs();
+    let mut fields = Vec::new();
+    for radix in radices.iter().rev() {
+        let (quotient, remainder) = magnitude.div_rem(&BigInt::from(*radix));
+        let width = (radix - 1).to_string().len();
+        fields.push(format!("{:0>width$}", remainder.to_string(), width = width));
+        magnitude = quotient;
+    }
+    fields.push(magnitude.to_string());
+    fields.reverse();
+
+    let sign = if value.is_negative() { "-" } else { "" };
+    format!("{}{}", sign, fields.join(":"))
+}
+
+/// Reads colon-separated decimal fields in a mixed radix. Fields may be left out from
+/// the front, so `1:30` is 90 with the radices of `H:MM:SS`, but every field after the
+/// first must be smaller than its radix.
+pub fn parse_mixed_radix(value: &str, radices: &[u32]) -> Option<BigInt> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value),
+    };
+    let fields: Vec<&str> = value.split(':').collect();
+    if fields.len() > radices.len() + 1 {
+        return None;
+    }
+
+    let radices = &radices[radices.len() + 1 - fields.len()..];
+    let mut total = BigInt::zero();
+    for (index, field) in fields.iter().enumerate() {
+        if field.is_empty() || !field.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let digit: BigInt = field.parse().ok()?;
+        if index > 0 {
+            let radix = BigInt::from(radices[index - 1]);
+            if digit >= radix {
+                return None;
+            }
+            total *= radix;
+        }
+        total += digit;
+    }
+    Some(if negative { -total } else { total })
+}
+
+/// Writes the value in the factorial number system, where the digit in position `i`,
+/// counting from zero at the right, weighs `i!` and is at most `i`. Digits above 9 are
+/// written as letters, and if any is above 35 the digits are written in decimal and
+/// separated by colons instead.
+pub fn to_factorial(value: &BigInt) -> String {
+    let mut magnitude = value.abs();
+    let mut digits = Vec::new();
+    let mut radix = 1u32;
+    loop {
+        let (quotient, remainder) = magnitude.div_rem(&BigInt::from(radix));
+        digits.push(remainder.to_u32().unwrap());
+        magnitude = quotient;
+        if magnitude.is_zero() {
+            break;
+        }
+        radix += 1;
+    }
+    digits.reverse();
+
+    let digits = if digits.iter().all(|digit| *digit < 36) {
+        digits
+            .iter()
+            .map(|digit| std::char::from_digit(*digit, 36).unwrap())
+            .collect()
+    } else {
+        let fields: Vec<String> = digits.iter().map(u32::to_string).collect();
+        fields.join(":")
+    };
+    let sign = if value.is_negative() { "-" } else { "" };
+    format!("{}{}", sign, digits)
+}
+
+/// Reads factorial number system digits, either as 0-9 and a-z or as colon-separated
+/// decimal digits. Each digit must be no larger than its position.
+pub fn parse_factorial(value: &str) -> Option<BigInt> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value),
+    };
+    let digits = if value.contains(':') {
+        value
+            .split(':')
+            .map(|field| field.parse::<u32>().ok())
+            .collect::<Option<Vec<u32>>>()?
+    } else {
+        value
+            .chars()
+            .map(|c| c.to_digit(36))
+            .collect::<Option<Vec<u32>>>()?
+    };
+    if digits.is_empty() {
+        return None;
+    }
+
+    let positions = digits.len() as u32;
+    let mut total = BigInt::zero();
+    for (index, digit) in digits.into_iter().enumerate() {
+        let position = positions - 1 - index as u32;
+        if digit > position {
+            return None;
+        }
+        total = total * BigInt::from(position + 1) + digit;
+    }
+    Some(if negative { -total } else { total })
+}
+
+/// Writes the value in base 12, with X and E for ten and eleven
+pub fn to_dozenal(value: &BigInt) -> String {
+    value.to_str_radix(12).replace('a', "X").replace('b', "E")
+}
+
+/// Rewrites the X and E digits of dozenal output in the given digit style
+pub fn style_dozenal(digits: &str, style: &DozenalDigits) -> String {
+    let (ten, eleven) = match style {
+        DozenalDigits::Xe => return digits.to_string(),
+        DozenalDigits::Pitman => ("\u{218a}", "\u{218b}"),
+        DozenalDigits::Ab => ("a", "b"),
+    };
+    digits.replace('X', ten).replace('E', eleven)
+}
+
+/// Reads base 12 digits, accepting X/E, the Pitman digits ↊/↋ and a/b for ten and eleven
+pub fn parse_dozenal(value: &str) -> Option<BigInt> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value),
+    };
+    let digits = value
+        .chars()
+        .map(|c| match c {
+            'X' | 'x' | 'A' | 'a' | '\u{218a}' => Some(10),
+            'E' | 'e' | 'B' | 'b' | '\u{218b}' => Some(11),
+            c => c.to_digit(10).map(|digit| digit as i32),
+        })
+        .collect::<Option<Vec<i32>>>();
+    let total = accumulate(digits?, 12)?;
+    Some(if negative { -total } else { total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Format = fn(&BigInt) -> String;
+    type Parse = fn(&str) -> Option<BigInt>;
+
+    fn big(value: i64) -> BigInt {
+        BigInt::from(value)
+    }
+
+    fn hms(value: &BigInt) -> String {
+        to_mixed_radix(value, &HMS)
+    }
+
+    fn parse_hms(value: &str) -> Option<BigInt> {
+        parse_mixed_radix(value, &HMS)
+    }
+
+    #[test]
+    fn writes_known_values() {
+        let cases: [(Format, i64, &str); 16] = [
+            (to_balanced_ternary, 0, "0"),
+            (to_balanced_ternary, 8, "10T"),
+            (to_balanced_ternary, -8, "T01"),
+            (to_negabinary, 0, "0"),
+            (to_negabinary, 6, "11010"),
+            (to_negabinary, -1, "11"),
+            (hms, 0, "0:00:00"),
+            (hms, 90061, "25:01:01"),
+            (hms, -90, "-0:01:30"),
+            (to_factorial, 0, "0"),
+            (to_factorial, 463, "341010"),
+            (to_factorial, -5, "-210"),
+            (to_dozenal, 0, "0"),
+            (to_dozenal, 143, "EE"),
+            (to_dozenal, -22, "-1X"),
+            (to_dozenal, 1728, "1000"),
+        ];
+        for (format, value, expected) in cases {
+            assert_eq!(format(&big(value)), expected, "{}", value);
+        }
+        assert_eq!(to_column(&big(1)).unwrap(), "A");
+        assert_eq!(to_column(&big(27)).unwrap(), "AA");
+        assert_eq!(to_column(&big(703)).unwrap(), "AAA");
+    }
+
+    #[test]
+    fn round_trips() {
+        let pairs: [(Format, Parse); 5] = [
+            (to_balanced_ternary, parse_balanced_ternary),
+            (to_negabinary, parse_negabinary),
+            (hms, parse_hms),
+            (to_factorial, parse_factorial),
+            (to_dozenal, parse_dozenal),
+        ];
+        for (format, parse) in pairs {
+            for value in (-1000..=1000).chain([i64::MIN, i64::MAX]) {
+                assert_eq!(parse(&format(&big(value))), Some(big(value)), "{}", value);
+            }
+        }
+        for value in (1..=1000).chain([i64::MAX]) {
+            let column = to_column(&big(value)).unwrap();
+            assert_eq!(parse_column(&column), Some(big(value)), "{}", value);
+        }
+    }
+
+    #[test]
+    fn reads_alternative_digits() {
+        assert_eq!(parse_balanced_ternary("1t"), Some(big(2)));
+        assert_eq!(parse_column("zz"), Some(big(702)));
+        assert_eq!(parse_hms("1:30"), Some(big(90)));
+        assert_eq!(parse_factorial("3:4:1:0:1:0"), Some(big(463)));
+        assert_eq!(parse_dozenal("\u{218b}\u{218a}"), Some(big(142)));
+        assert_eq!(parse_dozenal("ba"), Some(big(142)));
+        assert_eq!(
+            style_dozenal(&to_dozenal(&big(142)), &DozenalDigits::Pitman),
+            "\u{218b}\u{218a}"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_digits() {
+        for value in ["", "2", "1-1", "-1"] {
+            assert_eq!(parse_balanced_ternary(value), None, "{:?}", value);
+        }
+        for value in ["", "2", "-1"] {
+            assert_eq!(parse_negabinary(value), None, "{:?}", value);
+        }
+        for value in ["", "A1", "-A"] {
+            assert_eq!(parse_column(value), None, "{:?}", value);
+        }
+        for value in ["", "1:60", "1:2:3:4", "1::2", "1:x"] {
+            assert_eq!(parse_hms(value), None, "{:?}", value);
+        }
+        for value in ["", "2", "20", "1:2", "z"] {
+            assert_eq!(parse_factorial(value), None, "{:?}", value);
+        }
+        for value in ["", "-", "C", "1.5"] {
+            assert_eq!(parse_dozenal(value), None, "{:?}", value);
+        }
+        assert!(to_column(&big(0)).is_err());
+        assert!(to_column(&big(-1)).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use crate::base::radix;
+use crate::errors::BaseError;
+use crate::opts::{Base, Opt};
+use crate::Parsed;
+use num::bigint::{BigInt, BigUint};
+use num::{Integer, Signed, ToPrimitive, Zero};
+
+const SUPERSCRIPTS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
+/// Traces the conversion of the value: the positional expansion of its digits in the
+/// base it was read in, then the repeated division by each output base, whose
+/// remainders read from last to first are the digits in that base. The steps work on
+/// the magnitude, with the sign of a negative value put back at the end.
+pub fn explain(opt: &Opt, parsed: &Parsed) -> Result<String, BaseError> {
+    let num = parsed.integer()?.clone().into_inner();
+    let mut lines = Vec::new();
+    if let Some(from) = radix(parsed.base.clone()) {
+        lines.push(format!(
+            "Reading {}{} as {}:",
+            sign(&num),
+            num.magnitude().to_str_radix(from),
+            parsed.base.repr().to_lowercase()
+        ));
+        let steps = places(&num, from);
+        lines.extend(steps.into_iter().map(|step| format!("  {}", step)));
+    }
+
+    let bases: Vec<Base> = opt
+        .output_bases()
+        .into_iter()
+        .filter(|base| radix(base.clone()).is_some())
+        .collect();
+    if bases.is_empty() {
+        return Err(BaseError::ArgError {
+            message: "--explain needs an output base of binary, octal, decimal or hex",
+        });
+    }
+    for base in bases {
+        let to = radix(base.clone()).unwrap();
+        lines.push(format!(
+            "Writing {} in {}:",
+            num,
+            base.repr().to_lowercase()
+        ));
+        lines.extend(divisions(num.magnitude(), to));
+        lines.push(format!(
+            "  remainders from last to first: {}{}",
+            sign(&num),
+            num.magnitude().to_str_radix(to)
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Lists the weight and contribution of each digit of the value in the base it was
+/// read in, then their total
+pub fn place_values(parsed: &Parsed) -> Result<String, BaseError> {
+    let num = parsed.integer()?.clone().into_inner();
+    let radix = radix(parsed.base.clone()).ok_or(BaseError::ArgError {
+        message: "--places needs a value in binary, octal, decimal or hex",
+    })?;
+    Ok(places(&num, radix).join("\n"))
+}
+
+/// Returns a line for the weight and contribution of each digit of the magnitude, most
+/// significant first, then a line for the total
+fn places(num: &BigInt, radix: u32) -> Vec<String> {
+    let digits = num.magnitude().to_str_radix(radix);
+    let count = digits.len();
+    let mut lines: Vec<String> = digits
+        .chars()
+        .enumerate()
+        .map(|(index, digit)| {
+            let place = count - 1 - index;
+            let weight = BigUint::from(radix).pow(place as u32);
+            format!(
+                "{}×{}{} = {}",
+                digit,
+                radix,
+                superscript(place),
+                weight * digit.to_digit(radix).unwrap()
+            )
+        })
+        .collect();
+    lines.push(format!("total {}", num));
+    lines
+}
+
+/// Returns a line for each division by the radix, until the quotient is zero. Remainders
+/// above nine are followed by their digit.
+fn divisions(num: &BigUint, radix: u32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut rest = num.clone();
+    loop {
+        let (quotient, remainder) = rest.div_rem(&BigUint::from(radix));
+        let remainder = remainder.to_u32().unwrap();
+        let digit = std::char::from_digit(remainder, radix).unwrap();
+        let remainder = if remainder > 9 {
+            format!("{} ({})", remainder, digit)
+        } else {
+            remainder.to_string()
+        };
+        lines.push(format!(
+            "  {} ÷ {} = {} remainder {}",
+            rest, radix, quotient, remainder
+        ));
+        rest = quotient;
+        if rest.is_zero() {
+            return lines;
+        }
+    }
+}
+
+fn sign(num: &BigInt) -> &'static str {
+    if num.is_negative() {
+        "-"
+    } else {
+        ""
+    }
+}
+
+fn superscript(power: usize) -> String {
+    power
+        .to_string()
+        .chars()
+        .map(|digit| SUPERSCRIPTS[digit.to_digit(10).unwrap() as usize])
+        .collect()
+}
@@ -0,0 +1,399 @@
+use crate::base::{suffixed_base, Value};
+use crate::errors::BaseError;
+use crate::literal::strip_code_literal;
+use crate::opts::Base;
+use num::{bigint::BigInt, Signed, ToPrimitive, Zero};
+
+/// Most bits a result may have, so an expression such as `1 << 99999999999` fails
+/// rather than running out of memory
+pub const MAX_BITS: u64 = 1 << 24;
+
+/// Deepest nesting of parentheses and unary operators, so an expression cannot run
+/// out of stack
+const MAX_DEPTH: usize = 256;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
+    ShiftLeft,
+    ShiftRight,
+    LParen,
+    RParen,
+}
+
+/// Returns true if the given value looks like an arithmetic expression rather than a single number
+pub fn is_expression(value: &str) -> bool {
+    let mut prev = None;
+    for c in value.chars() {
+        match c {
+            '*' | '/' | '%' | '&' | '|' | '^' | '~' | '<' | '>' | '(' | ')' => return true,
+            c if c.is_whitespace() => return true,
+            // a leading sign belongs to the number, and `p-` is a hex float exponent
+            '+' | '-' if prev.is_some() && prev != Some('p') => return true,
+            _ => {}
+        }
+        prev = Some(c);
+    }
+    false
+}
+
+/// Returns true if the given value names the previous result, i.e. `_` or `last`
+pub fn is_variable(value: &str) -> bool {
+    value == "_" || value == "last"
+}
+
+/// Evaluates an arithmetic expression such as `0xff + 0b1010 * 3` or `(0xff << 8) | 0x0a`.
+/// Operators follow Rust precedence: unary `- ~`, then `* / %`, `+ -`, `<< >>`, `&`, `^`, `|`.
+/// Literals without a base prefix are parsed in the given input base, or otherwise in
+/// the base named by an assembly-style suffix such as `ffh`, or decimal, and
+/// `_` or `last` evaluate to the given previous result.
+pub fn evaluate(
+    expression: &str,
+    input: Option<Base>,
+    last: Option<&Value>,
+) -> Result<Value, BaseError> {
+    let tokens = tokenize(expression)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        suffixes: input.is_none(),
+        default_base: input.unwrap_or(Base::Dec),
+        last: last.cloned().map(Value::into_inner),
+        depth: 0,
+    };
+
+    let value = parser.parse_or()?;
+    if parser.position < parser.tokens.len() {
+        return Err(BaseError::EvalError {
+            message: "Unexpected token after end of expression",
+        });
+    }
+    Ok(Value::new(value))
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, BaseError> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                let mut literal = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    literal.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Number(literal));
+                continue;
+            }
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '%' => Token::Percent,
+            '&' => Token::Ampersand,
+            '|' => Token::Pipe,
+            '^' => Token::Caret,
+            '~' => Token::Tilde,
+            '<' | '>' => {
+                chars.next();
+                if chars.peek() != Some(&c) {
+                    return Err(BaseError::EvalError {
+                        message: "Comparison operators are not supported, use << or >> to shift",
+                    });
+                }
+                if c == '<' {
+                    Token::ShiftLeft
+                } else {
+                    Token::ShiftRight
+                }
+            }
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            _ => {
+                return Err(BaseError::EvalError {
+                    message: "Unknown operator in expression",
+                })
+            }
+        };
+        tokens.push(token);
+        chars.next();
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    default_base: Base,
+    /// Whether suffixes name the base, which they only do without an input base
+    suffixes: bool,
+    last: Option<BigInt>,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<BigInt, BaseError> {
+        let mut value = self.parse_xor()?;
+        while self.peek() == Some(&Token::Pipe) {
+            self.next();
+            value |= self.parse_xor()?;
+        }
+        Ok(value)
+    }
+
+    fn parse_xor(&mut self) -> Result<BigInt, BaseError> {
+        let mut value = self.parse_and()?;
+        while self.peek() == Some(&Token::Caret) {
+            self.next();
+            value ^= self.parse_and()?;
+        }
+        Ok(value)
+    }
+
+    fn parse_and(&mut self) -> Result<BigInt, BaseError> {
+        let mut value = self.parse_shift()?;
+        while self.peek() == Some(&Token::Ampersand) {
+            self.next();
+            value &= self.parse_shift()?;
+        }
+        Ok(value)
+    }
+
+    fn parse_shift(&mut self) -> Result<BigInt, BaseError> {
+        let mut value = self.parse_sum()?;
+        loop {
+            match self.peek() {
+                Some(Token::ShiftLeft) => {
+                    self.next();
+                    let amount = shift_amount(self.parse_sum()?)?;
+                    if !value.is_zero() {
+                        bounded(value.bits() + amount as u64)?;
+                    }
+                    value <<= amount;
+                }
+                Some(Token::ShiftRight) => {
+                    self.next();
+                    value >>= shift_amount(self.parse_sum()?)?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn parse_sum(&mut self) -> Result<BigInt, BaseError> {
+        let mut value = self.parse_product()?;
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.next();
+                    value += self.parse_product()?;
+                }
+                Some(Token::Minus) => {
+                    self.next();
+                    value -= self.parse_product()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<BigInt, BaseError> {
+        let mut value = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Star) => {
+                    self.next();
+                    let factor = self.parse_unary()?;
+                    bounded(value.bits() + factor.bits())?;
+                    value *= factor;
+                }
+                Some(Token::Slash) => {
+                    self.next();
+                    value /= non_zero(self.parse_unary()?)?;
+                }
+                Some(Token::Percent) => {
+                    self.next();
+                    value %= non_zero(self.parse_unary()?)?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    /// Parses a unary expression, through which every nested expression passes
+    fn parse_unary(&mut self) -> Result<BigInt, BaseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(BaseError::EvalError {
+                message: "Expression is nested too deeply",
+            });
+        }
+        self.depth += 1;
+        let value = self.parse_nested();
+        self.depth -= 1;
+        value
+    }
+
+    fn parse_nested(&mut self) -> Result<BigInt, BaseError> {
+        match self.peek() {
+            Some(Token::Minus) => {
+                self.next();
+                Ok(-self.parse_unary()?)
+            }
+            Some(Token::Plus) => {
+                self.next();
+                self.parse_unary()
+            }
+            Some(Token::Tilde) => {
+                self.next();
+                Ok(!self.parse_unary()?)
+            }
+            _ => self.parse_atom(),
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<BigInt, BaseError> {
+        match self.next() {
+            Some(Token::Number(literal)) => self.parse_literal(literal),
+            Some(Token::LParen) => {
+                let value = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(value),
+                    _ => Err(BaseError::EvalError {
+                        message: "Missing closing parenthesis",
+                    }),
+                }
+            }
+            _ => Err(BaseError::EvalError {
+                message: "Expected a number or an opening parenthesis",
+            }),
+        }
+    }
+
+    fn parse_literal(&self, literal: String) -> Result<BigInt, BaseError> {
+        if is_variable(&literal) {
+            return self.last.clone().ok_or(BaseError::EvalError {
+                message: "No previous result to refer to",
+            });
+        }
+
+        let literal = strip_code_literal(&literal);
+        let (base, digits) = if let Some(digits) = literal.strip_prefix("0x") {
+            (Base::Hex, digits)
+        } else if let Some(digits) = literal.strip_prefix("0b") {
+            (Base::Bin, digits)
+        } else if let Some(digits) = literal.strip_prefix("0o") {
+            (Base::Oct, digits)
+        } else if let Some(base) = suffixed_base(&literal).filter(|_| self.suffixes) {
+            (base, &*literal)
+        } else {
+            (self.default_base.clone(), &*literal)
+        };
+
+        Value::from(digits, base).map(Value::into_inner)
+    }
+}
+
+fn non_zero(value: BigInt) -> Result<BigInt, BaseError> {
+    if value.is_zero() {
+        Err(BaseError::EvalError {
+            message: "Division by zero",
+        })
+    } else {
+        Ok(value)
+    }
+}
+
+fn shift_amount(value: BigInt) -> Result<usize, BaseError> {
+    if value.is_negative() {
+        return Err(BaseError::EvalError {
+            message: "Shift amount must not be negative",
+        });
+    }
+    // shifting right by more than the bits of any value gives the same result
+    Ok(value
+        .to_usize()
+        .unwrap_or(usize::MAX)
+        .min(MAX_BITS as usize + 1))
+}
+
+/// Fails if a result of that many bits would be larger than MAX_BITS
+fn bounded(bits: u64) -> Result<(), BaseError> {
+    if bits > MAX_BITS {
+        Err(BaseError::EvalError {
+            message: "Result is too large, at more than 2^24 bits",
+        })
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(expression: &str) -> Result<String, BaseError> {
+        evaluate(expression, None, None).map(|value| value.into_inner().to_string())
+    }
+
+    #[test]
+    fn shifts() {
+        assert_eq!(eval("1 << 8").unwrap(), "256");
+        assert_eq!(eval("0xff0 >> 99999999999").unwrap(), "0");
+        assert_eq!(eval("0 << 99999999999").unwrap(), "0");
+        assert!(eval("1 << -1").is_err());
+    }
+
+    #[test]
+    fn suffixes_only_name_the_base_without_an_input_base() {
+        assert_eq!(eval("ffh + 1b").unwrap(), "256");
+        let hex = evaluate("ff + 1b", Some(Base::Hex), None).unwrap();
+        assert_eq!(hex.into_inner().to_string(), "282");
+    }
+
+    #[test]
+    fn bounds_large_results() {
+        assert!(matches!(
+            eval("1 << 99999999999"),
+            Err(BaseError::EvalError { .. })
+        ));
+        let bits = |expression| evaluate(expression, None, None).map(|v| v.bits());
+        assert!(bits("(1 << 16000000) * (1 << 16000000)").is_err());
+        assert_eq!(bits("1 << 16000000").unwrap(), 16000001);
+    }
+
+    #[test]
+    fn bounds_nesting() {
+        let nested = |depth| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(eval(&nested(100)).unwrap(), "1");
+        assert!(eval(&nested(100_000)).is_err());
+        assert!(eval(&"-".repeat(100_000)).is_err());
+    }
+}
@@ -0,0 +1,31 @@
+use crate::base::prefix;
+use crate::bit::{extract, parse_range};
+use crate::defs;
+use crate::errors::BaseError;
+use crate::info::unsigned;
+use crate::opts::{Base, Opt};
+use crate::Parsed;
+
+/// Decodes the value into `NAME=value` lines, one for each field of the register
+/// definition named by `spec`. Fields are values of the form `HI:LO` or a single bit
+/// index, printed in the output base or decimal.
+pub fn decode(opt: &Opt, parsed: &Parsed, spec: &str) -> Result<String, BaseError> {
+    let register = defs::load_table(spec)?;
+    let num = unsigned(parsed.integer()?, opt.width.or(parsed.width))?;
+    let output = opt.get_output().unwrap_or(Base::Dec);
+
+    let lines = register
+        .entries
+        .iter()
+        .map(|(name, range)| {
+            let (hi, lo) = parse_range(range)?;
+            let digits = extract(&num, hi, lo).to_base(output.clone())?;
+            let digits = match opt.get_prefix_style() {
+                Some(style) => prefix(&digits, output.prefix(&style)),
+                None => digits,
+            };
+            Ok(format!("{}={}", name, digits))
+        })
+        .collect::<Result<Vec<String>, BaseError>>()?;
+    Ok(lines.join("\n"))
+}
@@ -0,0 +1,109 @@
+use crate::base::Value;
+use crate::errors::BaseError;
+use crate::opts::{Base, Opt};
+use crate::{format_output, Number, Parsed};
+use num::bigint::{BigInt, BigUint};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::os::unix::io::AsRawFd;
+use std::{ptr, slice};
+
+/// Mapping is a read-only memory map of a whole file, unmapped when dropped
+struct Mapping {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+impl Mapping {
+    fn new(file: &File) -> Result<Mapping, BaseError> {
+        let error = BaseError::IoError {
+            message: "Unable to map input file",
+        };
+        let len = file.metadata().map_err(|_| error)?.len() as usize;
+        if len == 0 {
+            // empty mappings are rejected, so there is nothing to map
+            return Ok(Mapping {
+                ptr: ptr::null_mut(),
+                len,
+            });
+        }
+
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(BaseError::IoError {
+                message: "Unable to map input file",
+            });
+        }
+        Ok(Mapping { ptr, len })
+    }
+
+    fn bytes(&self) -> &[u8] {
+        if self.len == 0 {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(self.ptr as *const u8, self.len) }
+        }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            unsafe { libc::munmap(self.ptr, self.len) };
+        }
+    }
+}
+
+/// Prints the contents of the file as one big-endian integer, or with `word` as one
+/// big-endian integer of that many bytes per line. A shorter last word holds the
+/// bytes left over.
+pub fn run(opt: &Opt, path: &str, word: Option<usize>) -> Result<(), BaseError> {
+    let file = File::open(path).map_err(|_| BaseError::IoError {
+        message: "Unable to read input file",
+    })?;
+    let mapping = Mapping::new(&file)?;
+    let bytes = mapping.bytes();
+
+    match word {
+        Some(0) => Err(BaseError::ArgError {
+            message: "--word must be at least one byte",
+        }),
+        Some(word) => {
+            let stdout = io::stdout();
+            let mut writer = BufWriter::new(stdout.lock());
+            for chunk in bytes.chunks(word) {
+                let output = format_output(opt, &parsed(chunk))?;
+                writeln!(writer, "{}", output).map_err(|_| BaseError::IoError {
+                    message: "Unable to write to stdout",
+                })?;
+            }
+            writer.flush().map_err(|_| BaseError::IoError {
+                message: "Unable to write to stdout",
+            })
+        }
+        None => {
+            println!("{}", format_output(opt, &parsed(bytes))?);
+            Ok(())
+        }
+    }
+}
+
+/// Reads the bytes as an unsigned big-endian number as wide as the bytes, so leading
+/// zero bytes are kept when padding output
+fn parsed(bytes: &[u8]) -> Parsed {
+    Parsed {
+        base: Base::Hex,
+        num: Number::Integer(Value::new(BigInt::from(BigUint::from_bytes_be(bytes)))),
+        width: Some(bytes.len() * 8).filter(|width| *width > 0),
+        signed: false,
+    }
+}
@@ -0,0 +1,137 @@
+use crate::base::{detect_base, strip_base_prefix, DetectStrategy, Value};
+use crate::command::FilterOpts;
+use crate::errors::BaseError;
+use crate::opts::{Base, Opt};
+use crate::regex::Regex;
+use crate::{format_number, Number, Parsed};
+use std::io::{self, BufRead, BufWriter, Write};
+
+/// Finds the numbers in text: each match of the pattern, or of its first group, or
+/// otherwise each word valid in the `from` base, or written with a base prefix
+pub struct Finder {
+    from: Option<Base>,
+    pattern: Option<Regex>,
+}
+
+impl Finder {
+    pub fn new(from: Option<Base>, pattern: Option<&str>) -> Result<Finder, BaseError> {
+        Ok(Finder {
+            from,
+            pattern: pattern.map(Regex::new).transpose()?,
+        })
+    }
+
+    /// Returns the byte range of each number in the line with its value and base
+    pub fn numbers(&self, line: &str) -> Vec<(usize, usize, Parsed)> {
+        let spans = match &self.pattern {
+            Some(pattern) => pattern
+                .find_all(line)
+                .iter()
+                .filter_map(|found| found.first_group())
+                .collect(),
+            None => words(line),
+        };
+        spans
+            .into_iter()
+            .filter_map(|(start, end)| {
+                let parsed = self.parse(&line[start..end])?;
+                Some((start, end, parsed))
+            })
+            .collect()
+    }
+
+    fn parse(&self, token: &str) -> Option<Parsed> {
+        let digits = token.strip_prefix('-').unwrap_or(token);
+        let base = match &self.from {
+            // any word valid in the base is read, so `--from hex` converts `ff` and `dead`
+            Some(base) => base.clone(),
+            None if self.pattern.is_some() => detect_base(token, DetectStrategy::Default).ok()?,
+            None => vec![Base::Hex, Base::Bin, Base::Oct]
+                .into_iter()
+                .find(|base| strip_base_prefix(digits, base).len() < digits.len())?,
+        };
+        let num = Value::from(token, base.clone()).ok()?;
+        Some(Parsed {
+            base,
+            num: Number::Integer(num),
+            width: None,
+            signed: false,
+        })
+    }
+}
+
+/// Returns the byte ranges of the runs of letters, digits and underscores in the line,
+/// leaving out those joined to others by a dot, such as versions or IPv4 addresses.
+/// A minus sign before a word is included unless it follows another word, as in `a-1`.
+fn words(line: &str) -> Vec<(usize, usize)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut words = Vec::new();
+    let mut start = None;
+    for (index, c) in line.char_indices().chain(Some((line.len(), ' '))) {
+        match (start, is_word(c)) {
+            (None, true) => start = Some(index),
+            (Some(from), false) => {
+                let dotted_before =
+                    line[..from].ends_with('.') && line[..from - 1].ends_with(is_word);
+                let dotted_after = c == '.' && line[index + 1..].starts_with(is_word);
+                let signed = line[..from].ends_with('-') && !line[..from - 1].ends_with(is_word);
+                if !dotted_before && !dotted_after {
+                    words.push((if signed { from - 1 } else { from }, index));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+/// Copies stdin to stdout, replacing each number found with the number in the `to` base,
+/// or with `annotate`, following it with the converted number in parentheses
+pub fn run(opt: &Opt, filter: &FilterOpts, annotate: bool) -> Result<(), BaseError> {
+    let finder = Finder::new(filter.from.clone(), filter.pattern.as_deref())?;
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    let mut line = String::new();
+    let mut reader = stdin.lock();
+    loop {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|_| BaseError::IoError {
+                message: "Unable to read from stdin",
+            })?;
+        if read == 0 {
+            break;
+        }
+
+        // the line ending is left out so `$` matches at the end of the line
+        let text = line.trim_end_matches(&['\n', '\r'][..]);
+        let mut output = String::with_capacity(line.len());
+        let mut copied = 0;
+        for (start, end, parsed) in finder.numbers(text) {
+            if let Ok(converted) = format_number(opt, &parsed, filter.to.clone()) {
+                if annotate {
+                    output.push_str(&line[copied..end]);
+                    output.push_str(" (");
+                    output.push_str(&converted);
+                    output.push(')');
+                } else {
+                    output.push_str(&line[copied..start]);
+                    output.push_str(&converted);
+                }
+                copied = end;
+            }
+        }
+        output.push_str(&line[copied..]);
+        writer
+            .write_all(output.as_bytes())
+            .map_err(|_| BaseError::IoError {
+                message: "Unable to write to stdout",
+            })?;
+    }
+    writer.flush().map_err(|_| BaseError::IoError {
+        message: "Unable to write to stdout",
+    })
+}
@@ -0,0 +1,62 @@
+use crate::base::{prefix, Value};
+use crate::defs;
+use crate::errors::BaseError;
+use crate::expr;
+use crate::info::unsigned;
+use crate::opts::{Base, Opt, PrefixStyle};
+use crate::Parsed;
+use num::{bigint::BigInt, Zero};
+
+/// Lists the named flags set in the value, one per line, followed by any set bits not
+/// covered by a flag. A value with no bits set prints the name of a zero mask, if there
+/// is one, or `none`. `spec` is either a list such as `READ=0x1,WRITE=0x2` or a
+/// definition file of names and masks, e.g. `perms.toml#MODE`.
+pub fn decode(opt: &Opt, parsed: &Parsed, spec: &str) -> Result<String, BaseError> {
+    let flags = if spec.contains('=') {
+        parse_list(spec)?
+    } else {
+        defs::load_table(spec)?.entries
+    };
+    let num = unsigned(parsed.integer()?, opt.width.or(parsed.width))?.into_inner();
+
+    let mut lines = Vec::new();
+    let mut known = BigInt::zero();
+    let mut empty = None;
+    for (name, mask) in &flags {
+        let mask = expr::evaluate(mask, None, None)?.into_inner();
+        if mask.is_zero() {
+            empty = empty.or(Some(name));
+        } else if (&num & &mask) == mask {
+            lines.push(name.to_string());
+        }
+        known |= mask;
+    }
+    if num.is_zero() {
+        return Ok(empty.map_or("none", |name| name.as_str()).to_string());
+    }
+
+    let unknown = num & !known;
+    if !unknown.is_zero() {
+        let output = opt.get_output().unwrap_or(Base::Hex);
+        let digits = Value::new(unknown).to_base(output.clone())?;
+        let style = opt.get_prefix_style().unwrap_or(PrefixStyle::Rust);
+        lines.push(format!(
+            "unknown={}",
+            prefix(&digits, output.prefix(&style))
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn parse_list(spec: &str) -> Result<Vec<(String, String)>, BaseError> {
+    spec.split(',')
+        .map(|flag| match flag.split_once('=') {
+            Some((name, mask)) if !name.trim().is_empty() => {
+                Ok((name.trim().to_string(), mask.trim().to_string()))
+            }
+            _ => Err(BaseError::ArgError {
+                message: "Flags are written as NAME=MASK, e.g. READ=0x1,WRITE=0x2",
+            }),
+        })
+        .collect()
+}
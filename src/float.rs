@@ -0,0 +1,145 @@
+use crate::base::radix;
+use crate::errors::{BaseError, Syntax};
+use crate::opts::Base;
+
+/// IEEE 754 binary interchange formats
+#[derive(Debug, Clone, Copy)]
+pub enum FloatFormat {
+    F32,
+    F64,
+}
+
+impl FloatFormat {
+    fn exponent_bits(&self) -> u32 {
+        match self {
+            FloatFormat::F32 => 8,
+            FloatFormat::F64 => 11,
+        }
+    }
+
+    fn mantissa_bits(&self) -> u32 {
+        match self {
+            FloatFormat::F32 => 23,
+            FloatFormat::F64 => 52,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        1 + self.exponent_bits() + self.mantissa_bits()
+    }
+
+    /// Picks the format of a bit pattern by its width: single precision for patterns of
+    /// up to 32 bits and double precision for wider ones. Hex and binary patterns are as
+    /// wide as their digits, so leading zeros count, as in 0000000000000001.
+    pub fn of_pattern(value: &str, base: Base) -> FloatFormat {
+        let digits = match base {
+            Base::Hex => value.trim_start_matches("0x"),
+            _ => value,
+        };
+        let bits = match base {
+            Base::Bin => digits.len() as u32,
+            Base::Hex => digits.len() as u32 * 4,
+            _ => radix(base)
+                .and_then(|radix| u64::from_str_radix(digits, radix).ok())
+                .map(|bits| u64::BITS - bits.leading_zeros())
+                .unwrap_or(u64::BITS),
+        };
+        if bits <= FloatFormat::F32.width() {
+            FloatFormat::F32
+        } else {
+            FloatFormat::F64
+        }
+    }
+}
+
+/// FloatBits holds the raw bit pattern of a float in the given format
+pub struct FloatBits {
+    bits: u64,
+    format: FloatFormat,
+}
+
+impl FloatBits {
+    /// Parses a decimal float such as `3.5` or `-1e10`
+    pub fn from_float(value: &str, format: FloatFormat) -> Result<FloatBits, BaseError> {
+        let bits = match format {
+            FloatFormat::F32 => value.parse::<f32>().map(|f| f.to_bits() as u64),
+            FloatFormat::F64 => value.parse::<f64>().map(f64::to_bits),
+        }
+        .map_err(|_| BaseError::InvalidSyntax {
+            syntax: Syntax::Float,
+        })?;
+
+        Ok(FloatBits { bits, format })
+    }
+
+    /// Parses a raw bit pattern given in the input base
+    pub fn from_bits(value: &str, base: Base, format: FloatFormat) -> Result<FloatBits, BaseError> {
+        let digits = match base {
+            Base::Hex => value.trim_start_matches("0x"),
+            _ => value,
+        };
+        let radix = radix(base).ok_or(BaseError::ArgError {
+            message: "Float bit patterns must be given in a positional base",
+        })?;
+        let bits = u64::from_str_radix(digits, radix).map_err(|_| BaseError::InvalidSyntax {
+            syntax: Syntax::FloatBits,
+        })?;
+        if format.width() < 64 && bits >> format.width() != 0 {
+            return Err(BaseError::OverflowError {
+                message: "Bit pattern is wider than the float format",
+            });
+        }
+
+        Ok(FloatBits { bits, format })
+    }
+
+    pub fn sign(&self) -> u64 {
+        self.bits >> (self.format.width() - 1)
+    }
+
+    pub fn exponent(&self) -> u64 {
+        (self.bits >> self.format.mantissa_bits()) & ((1 << self.format.exponent_bits()) - 1)
+    }
+
+    pub fn mantissa(&self) -> u64 {
+        self.bits & ((1 << self.format.mantissa_bits()) - 1)
+    }
+
+    pub fn to_float_string(&self) -> String {
+        match self.format {
+            FloatFormat::F32 => format!("{:?}", f32::from_bits(self.bits as u32)),
+            FloatFormat::F64 => format!("{:?}", f64::from_bits(self.bits)),
+        }
+    }
+
+    /// Renders the sign, exponent and mantissa fields along with the raw bit pattern
+    pub fn describe(&self) -> String {
+        let exponent_bits = self.format.exponent_bits() as usize;
+        let mantissa_bits = self.format.mantissa_bits() as usize;
+        let bias = (1 << (exponent_bits - 1)) - 1;
+        let exponent = format!("{:0width$b}", self.exponent(), width = exponent_bits);
+        let mantissa = format!("{:0width$b}", self.mantissa(), width = mantissa_bits);
+
+        let unbiased = if self.exponent() == 0 {
+            "subnormal".to_string()
+        } else if self.exponent() == (1 << exponent_bits) - 1 {
+            "infinity or NaN".to_string()
+        } else {
+            format!("2^{}", self.exponent() as i64 - bias)
+        };
+
+        [
+            format!("Value:    {}", self.to_float_string()),
+            format!("Sign:     {}", self.sign()),
+            format!("Exponent: {} ({})", exponent, unbiased),
+            format!("Mantissa: {}", mantissa),
+            format!(
+                "Hex:      0x{:0width$x}",
+                self.bits,
+                width = self.format.width() as usize / 4
+            ),
+            format!("Binary:   {} {} {}", self.sign(), exponent, mantissa),
+        ]
+        .join("\n")
+    }
+}
@@ -0,0 +1,123 @@
+use crate::base::{is_valid, radix, strip_base_prefix, strip_base_suffix};
+use crate::errors::{BaseError, Syntax};
+use crate::expr::MAX_BITS;
+use crate::opts::Base;
+use num::{
+    bigint::{BigInt, BigUint},
+    rational::BigRational,
+    Num, One, Signed, ToPrimitive, Zero,
+};
+
+/// Fraction holds a value with a fractional part, e.g. `3.14159` or `0x1.8p0`
+pub struct Fraction {
+    value: BigRational,
+}
+
+impl Fraction {
+    pub fn new(value: BigRational) -> Fraction {
+        Fraction { value }
+    }
+
+    /// Returns the bits of the larger of the numerator and denominator
+    pub fn bits(&self) -> u64 {
+        self.value.numer().bits().max(self.value.denom().bits())
+    }
+
+    /// Returns true if the given value should be parsed as a fraction rather than an integer
+    pub fn is_fractional(value: &str) -> bool {
+        let digits = value.trim_start_matches('-');
+        digits.contains('.') || (digits.starts_with("0x") && digits.contains('p'))
+    }
+
+    pub fn from(value: &str, base: Base) -> Result<Fraction, BaseError> {
+        let (negative, value) = match value.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, value),
+        };
+        let value = strip_base_suffix(strip_base_prefix(value, &base), &base);
+
+        // hexadecimal floats may carry a binary exponent, e.g. 0x1.8p3
+        let (mantissa, exponent) = match base {
+            Base::Hex => match value.split_once('p') {
+                Some((mantissa, exponent)) => (
+                    mantissa,
+                    exponent
+                        .parse::<i32>()
+                        .map_err(|_| BaseError::InvalidSyntax {
+                            syntax: Syntax::Exponent,
+                        })?,
+                ),
+                None => (value, 0),
+            },
+            _ => (value, 0),
+        };
+
+        let (whole, fractional) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let digits = format!("{}{}", whole, fractional);
+        if digits.is_empty() || !is_valid(base.clone(), &digits) {
+            return Err(Fraction::get_parse_error());
+        }
+
+        if exponent.unsigned_abs() as u64 > MAX_BITS {
+            return Err(BaseError::OverflowError {
+                message: "Exponent: must be at most 2^24",
+            });
+        }
+
+        let radix = radix(base).ok_or_else(Fraction::get_base_error)?;
+        let numerator = BigUint::from_str_radix(digits.as_str(), radix)
+            .map_err(|_| Fraction::get_parse_error())?;
+        let denominator = BigUint::from(radix).pow(fractional.len() as u32);
+        let mut value = BigRational::new(BigInt::from(numerator), BigInt::from(denominator));
+        if exponent >= 0 {
+            value *= BigInt::one() << exponent as usize;
+        } else {
+            value /= BigInt::one() << exponent.unsigned_abs() as usize;
+        }
+
+        Ok(Fraction {
+            value: if negative { -value } else { value },
+        })
+    }
+
+    /// Formats the value in the given base, with at most `precision` digits after the radix point
+    pub fn to_base(&self, base: Base, precision: usize) -> Result<String, BaseError> {
+        let radix = BigInt::from(radix(base).ok_or_else(Fraction::get_base_error)?);
+        let magnitude = self.value.abs();
+        let whole = magnitude.trunc();
+        let mut fractional = magnitude - &whole;
+
+        let mut result = String::new();
+        if self.value.is_negative() {
+            result.push('-');
+        }
+        result.push_str(&whole.to_integer().to_str_radix(radix.to_u32().unwrap()));
+
+        if !fractional.is_zero() && precision > 0 {
+            result.push('.');
+            for _ in 0..precision {
+                if fractional.is_zero() {
+                    break;
+                }
+                fractional *= &radix;
+                let digit = fractional.trunc();
+                fractional -= &digit;
+                let digit = digit.to_integer().to_u32().unwrap();
+                result.push(std::char::from_digit(digit, radix.to_u32().unwrap()).unwrap());
+            }
+        }
+        Ok(result)
+    }
+
+    fn get_base_error() -> BaseError {
+        BaseError::ArgError {
+            message: "Fractions can only be written in positional bases",
+        }
+    }
+
+    fn get_parse_error() -> BaseError {
+        BaseError::InvalidSyntax {
+            syntax: Syntax::Fraction,
+        }
+    }
+}
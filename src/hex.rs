@@ -0,0 +1,145 @@
+use num::bigint::BigUint;
+
+/// Number of hex digits decoded or encoded per chunk, as one 128-bit vector
+const CHUNK: usize = 16;
+
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Decodes hex digits into a number a chunk at a time, rather than digit by digit.
+/// Returns None if the digits are empty or contain anything but 0-9, a-f and A-F.
+pub fn decode(digits: &str) -> Option<BigUint> {
+    let digits = digits.as_bytes();
+    if digits.is_empty() {
+        return None;
+    }
+
+    // an odd leading digit is the low nibble of the first byte
+    let (head, rest) = digits.split_at(digits.len() % 2);
+    let mut bytes = Vec::with_capacity(digits.len().div_ceil(2));
+    if let Some(&digit) = head.first() {
+        bytes.push(nibble(digit)?);
+    }
+
+    let mut chunks = rest.chunks_exact(CHUNK);
+    for chunk in &mut chunks {
+        decode_chunk(chunk, &mut bytes)?;
+    }
+    for pair in chunks.remainder().chunks_exact(2) {
+        bytes.push(nibble(pair[0])? << 4 | nibble(pair[1])?);
+    }
+    Some(BigUint::from_bytes_be(&bytes))
+}
+
+/// Encodes the number as lowercase hex digits without leading zeros
+pub fn encode(value: &BigUint) -> String {
+    let bytes = value.to_bytes_be();
+    let mut digits = Vec::with_capacity(bytes.len() * 2);
+    let mut chunks = bytes.chunks_exact(CHUNK / 2);
+    for chunk in &mut chunks {
+        encode_chunk(chunk, &mut digits);
+    }
+    for byte in chunks.remainder() {
+        digits.push(DIGITS[(byte >> 4) as usize]);
+        digits.push(DIGITS[(byte & 0xf) as usize]);
+    }
+
+    let start = digits
+        .iter()
+        .position(|digit| *digit != b'0')
+        .unwrap_or(digits.len() - 1);
+    digits.drain(..start);
+    String::from_utf8(digits).unwrap()
+}
+
+fn nibble(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        b'A'..=b'F' => Some(digit - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+fn decode_chunk(chunk: &[u8], bytes: &mut Vec<u8>) -> Option<()> {
+    for pair in chunk.chunks_exact(2) {
+        bytes.push(nibble(pair[0])? << 4 | nibble(pair[1])?);
+    }
+    Some(())
+}
+
+#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+fn encode_chunk(chunk: &[u8], digits: &mut Vec<u8>) {
+    for byte in chunk {
+        digits.push(DIGITS[(byte >> 4) as usize]);
+        digits.push(DIGITS[(byte & 0xf) as usize]);
+    }
+}
+
+/// Decodes 16 digits into 8 bytes with SSE2, which every x86_64 CPU has
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+fn decode_chunk(chunk: &[u8], bytes: &mut Vec<u8>) -> Option<()> {
+    use std::arch::x86_64::*;
+
+    let mut out = [0u8; CHUNK / 2];
+    // SAFETY: SSE2 is part of the x86_64 baseline, the load reads exactly the 16
+    // bytes of the chunk and the store writes exactly the 8 bytes of `out`
+    let valid = unsafe {
+        let c = _mm_loadu_si128(chunk.as_ptr() as *const __m128i);
+        let lower = _mm_or_si128(c, _mm_set1_epi8(0x20));
+        // bytes of 0x80 and above compare as negative, so they fail both ranges
+        let is_digit = _mm_and_si128(
+            _mm_cmpgt_epi8(c, _mm_set1_epi8(b'0' as i8 - 1)),
+            _mm_cmplt_epi8(c, _mm_set1_epi8(b'9' as i8 + 1)),
+        );
+        let is_letter = _mm_and_si128(
+            _mm_cmpgt_epi8(lower, _mm_set1_epi8(b'a' as i8 - 1)),
+            _mm_cmplt_epi8(lower, _mm_set1_epi8(b'f' as i8 + 1)),
+        );
+        let nibbles = _mm_or_si128(
+            _mm_and_si128(is_digit, _mm_sub_epi8(c, _mm_set1_epi8(b'0' as i8))),
+            _mm_and_si128(
+                is_letter,
+                _mm_sub_epi8(lower, _mm_set1_epi8(b'a' as i8 - 10)),
+            ),
+        );
+
+        // each 16-bit lane holds a high nibble in its low byte and a low nibble above it
+        let high = _mm_slli_epi16(_mm_and_si128(nibbles, _mm_set1_epi16(0xff)), 4);
+        let low = _mm_srli_epi16(nibbles, 8);
+        let packed = _mm_packus_epi16(_mm_or_si128(high, low), _mm_setzero_si128());
+        _mm_storel_epi64(out.as_mut_ptr() as *mut __m128i, packed);
+        _mm_movemask_epi8(_mm_or_si128(is_digit, is_letter)) == 0xffff
+    };
+    if valid {
+        bytes.extend_from_slice(&out);
+        Some(())
+    } else {
+        None
+    }
+}
+
+/// Encodes 8 bytes as 16 digits with SSE2
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+fn encode_chunk(chunk: &[u8], digits: &mut Vec<u8>) {
+    use std::arch::x86_64::*;
+
+    let mut out = [0u8; CHUNK];
+    // SAFETY: SSE2 is part of the x86_64 baseline, the load reads exactly the 8 bytes
+    // of the chunk and the store writes exactly the 16 bytes of `out`
+    unsafe {
+        let bytes = _mm_loadl_epi64(chunk.as_ptr() as *const __m128i);
+        let mask = _mm_set1_epi8(0xf);
+        let high = _mm_and_si128(_mm_srli_epi16(bytes, 4), mask);
+        let low = _mm_and_si128(bytes, mask);
+        let nibbles = _mm_unpacklo_epi8(high, low);
+        // nibbles above 9 skip the characters between '9' and 'a'
+        let letters = _mm_and_si128(
+            _mm_cmpgt_epi8(nibbles, _mm_set1_epi8(9)),
+            _mm_set1_epi8(b'a' as i8 - b'0' as i8 - 10),
+        );
+        let ascii = _mm_add_epi8(_mm_add_epi8(nibbles, _mm_set1_epi8(b'0' as i8)), letters);
+        _mm_storeu_si128(out.as_mut_ptr() as *mut __m128i, ascii);
+    }
+    digits.extend_from_slice(&out);
+}
@@ -0,0 +1,138 @@
+use crate::base::Value;
+use crate::opts::Endian;
+use num::bigint::{BigInt, BigUint};
+
+/// Bytes shown per line when no width is given, as with xxd
+pub const DEFAULT_WIDTH: usize = 16;
+
+/// Fewest digits in the offset of a dump line, so that plain hex values are not read as dumps
+const MIN_OFFSET_DIGITS: usize = 6;
+
+/// Writes the bytes of the value in xxd style: the offset of each line, then its bytes
+/// in hex, in groups of two, then the bytes as ASCII with a dot for unprintable ones
+pub fn to_hexdump(value: &Value, width: usize) -> String {
+    let bytes = value.to_bytes(Endian::Be);
+    // two digits per byte, and a space between each group of two bytes
+    let columns = width * 2 + width.div_ceil(2) - 1;
+    bytes
+        .chunks(width)
+        .enumerate()
+        .map(|(line, chunk)| {
+            let hex = chunk
+                .chunks(2)
+                .map(|group| group.iter().map(|byte| format!("{:02x}", byte)).collect())
+                .collect::<Vec<String>>()
+                .join(" ");
+            let ascii: String = chunk
+                .iter()
+                .map(|byte| match byte {
+                    0x20..=0x7e => *byte as char,
+                    _ => '.',
+                })
+                .collect();
+            format!(
+                "{:08x}: {:<columns$}  {}",
+                line * width,
+                hex,
+                ascii,
+                columns = columns
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Reads the bytes of pasted xxd or hexdump -C output back into a big-endian number
+pub fn parse(value: &str) -> Option<BigInt> {
+    parse_bytes(value).map(|bytes| BigInt::from(BigUint::from_bytes_be(&bytes)))
+}
+
+/// Returns true if the value is xxd or hexdump -C output
+pub fn is_hexdump(value: &str) -> bool {
+    parse_bytes(value).is_some()
+}
+
+/// Returns true if the line starts a dump written by xxd, with a colon after the offset,
+/// or by hexdump -C, with an ASCII gutter between bars. Lines of whitespace-separated
+/// numbers may otherwise read as a dump line without a gutter.
+pub fn is_dump_line(line: &str) -> bool {
+    let line = line.trim();
+    let digits = line
+        .find(|c: char| !c.is_ascii_hexdigit())
+        .unwrap_or(line.len());
+    let rest = &line[digits..];
+    (rest.starts_with(':') || (rest.starts_with(' ') && rest.ends_with('|'))) && is_hexdump(line)
+}
+
+/// Collects the bytes of each line, checking that each line starts at the offset of
+/// the bytes before it. A `*` line, as written by hexdump -C for repeated lines, repeats
+/// the line before it up to the next offset.
+fn parse_bytes(value: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut start = None;
+    let mut previous = Vec::new();
+    let mut squeezed = false;
+    for line in value.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "*" {
+            squeezed = true;
+            continue;
+        }
+
+        let digits = line
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(line.len());
+        if digits < MIN_OFFSET_DIGITS {
+            return None;
+        }
+        let offset = usize::from_str_radix(&line[..digits], 16).ok()?;
+        // pasted lines may start part way through a dump
+        let position = offset.checked_sub(*start.get_or_insert(offset))?;
+        if squeezed {
+            if previous.is_empty() {
+                return None;
+            }
+            while bytes.len() < position {
+                bytes.extend_from_slice(&previous);
+            }
+            squeezed = false;
+        }
+        if bytes.len() != position {
+            return None;
+        }
+
+        let rest = &line[digits..];
+        let columns = match rest.strip_prefix(':') {
+            // xxd pads the hex columns, so its ASCII gutter starts after the first double space
+            Some(columns) => columns.trim_start().split("  ").next().unwrap(),
+            // hexdump -C puts its gutter between bars, ending with a line of just the length
+            None if rest.is_empty() || rest.starts_with(' ') => rest.split('|').next().unwrap(),
+            None => return None,
+        };
+        previous = decode_columns(columns)?;
+        bytes.extend_from_slice(&previous);
+    }
+
+    if squeezed || bytes.is_empty() {
+        None
+    } else {
+        Some(bytes)
+    }
+}
+
+/// Decodes whitespace-separated groups of hex digit pairs
+fn decode_columns(columns: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    for group in columns.split_whitespace() {
+        if !group.len().is_multiple_of(2) || !group.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        for index in (0..group.len()).step_by(2) {
+            bytes.push(u8::from_str_radix(&group[index..index + 2], 16).ok()?);
+        }
+    }
+    Some(bytes)
+}
@@ -0,0 +1,152 @@
+use crate::base::radix;
+use crate::base::Value;
+use crate::errors::BaseError;
+use crate::opts::Opt;
+use crate::{parse_number, Parsed};
+use num::{bigint::BigUint, Signed, Zero};
+
+/// Prints the properties of the value
+pub fn run(opt: &Opt, value: &str) -> Result<(), BaseError> {
+    let parsed = parse_number(opt, value, None)?;
+    let width = opt.width.or(parsed.width);
+    let num = unsigned(parsed.integer()?, width)?;
+    println!("{}", describe(&num, width));
+    Ok(())
+}
+
+/// Answers the --popcount, --msb, --ctz or a digit query for the value, if one was given
+pub fn query(opt: &Opt, parsed: &Parsed) -> Result<Option<String>, BaseError> {
+    if let Some(answer) = digit_query(opt, parsed)? {
+        return Ok(Some(answer));
+    }
+    if !(opt.popcount || opt.msb || opt.ctz) {
+        return Ok(None);
+    }
+
+    let width = opt.width.or(parsed.width);
+    let num = unsigned(parsed.integer()?, width)?;
+    // both count from a set bit, so neither is defined for zero
+    let no_set_bits = BaseError::ArgError {
+        message: "Zero has no set bits",
+    };
+    let answer = if opt.popcount {
+        num.popcount()
+    } else if opt.msb {
+        num.highest_bit().ok_or(no_set_bits)?
+    } else {
+        num.trailing_zeros().ok_or(no_set_bits)?
+    };
+    Ok(Some(answer.to_string()))
+}
+
+/// Answers the --digit-sum or --digital-root query, which depend on the digits of the
+/// value in the base it was read in, or the --divisible-by query, if one was given
+fn digit_query(opt: &Opt, parsed: &Parsed) -> Result<Option<String>, BaseError> {
+    if let Some(divisor) = &opt.divisible_by {
+        let num = parsed.integer()?.clone().into_inner();
+        let divisor = parse_number(opt, divisor, None)?
+            .integer()?
+            .clone()
+            .into_inner();
+        if divisor.is_zero() {
+            return Err(BaseError::ArgError {
+                message: "--divisible-by must not be zero",
+            });
+        }
+        let divisible = (num % divisor).is_zero();
+        return Ok(Some(if divisible { "yes" } else { "no" }.to_string()));
+    }
+    if !(opt.digit_sum || opt.digital_root) {
+        return Ok(None);
+    }
+
+    let radix = radix(parsed.base.clone()).ok_or(BaseError::ArgError {
+        message: "Digit sums need a value in binary, octal, decimal or hex",
+    })?;
+    let num = parsed.integer()?.clone().into_inner();
+    let num = num.magnitude();
+    let answer = if opt.digit_sum {
+        let digits = num.to_radix_be(radix);
+        BigUint::from(digits.iter().map(|digit| *digit as u64).sum::<u64>())
+    } else if num.is_zero() {
+        BigUint::zero()
+    } else {
+        // repeated digit sums keep the value modulo one less than the radix
+        (num - 1u32) % (radix - 1) + 1u32
+    };
+    Ok(Some(answer.to_string()))
+}
+
+/// Returns the width, or the whole number of bits in the bytes holding the value
+fn bit_width(num: &Value, width: Option<usize>) -> u64 {
+    width
+        .map(|width| width as u64)
+        .unwrap_or(num.bits().div_ceil(8) * 8)
+}
+
+/// Returns the value as an unsigned bit pattern. Negative values need a width to be
+/// represented in two's complement.
+pub fn unsigned(num: &Value, width: Option<usize>) -> Result<Value, BaseError> {
+    match width {
+        Some(width) => num.to_unsigned(width),
+        None if num.clone().into_inner().is_negative() => Err(BaseError::ArgError {
+            message: "Negative values require --width",
+        }),
+        None => Ok(num.clone()),
+    }
+}
+
+/// Describes the bit properties of an unsigned value. Leading zeros are counted up to
+/// the width, or the whole number of bytes holding the value.
+pub fn describe(num: &Value, width: Option<usize>) -> String {
+    let bytes = num.bits().div_ceil(8);
+    let width = bit_width(num, width);
+    let popcount = num.popcount();
+    let highest = num.highest_bit();
+
+    [
+        format!(
+            "Bits:           {}",
+            highest.map(|bit| bit + 1).unwrap_or(0)
+        ),
+        format!("Bytes:          {}", bytes),
+        format!("Popcount:       {}", popcount),
+        format!(
+            "Leading zeros:  {}",
+            width - highest.map(|bit| bit + 1).unwrap_or(0)
+        ),
+        format!("Trailing zeros: {}", num.trailing_zeros().unwrap_or(width)),
+        format!(
+            "Parity:         {}",
+            if popcount.is_multiple_of(2) {
+                "even"
+            } else {
+                "odd"
+            }
+        ),
+        format!(
+            "Power of two:   {}",
+            if popcount == 1 { "yes" } else { "no" }
+        ),
+        format!(
+            "Highest bit:    {}",
+            highest
+                .map(|bit| bit.to_string())
+                .unwrap_or_else(|| "none".to_string())
+        ),
+    ]
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigInt;
+
+    #[test]
+    fn describes_zero_with_no_bits() {
+        let description = describe(&Value::new(BigInt::from(0)), None);
+        assert!(description.starts_with("Bits:           0\n"));
+        assert!(description.ends_with("Highest bit:    none"));
+    }
+}
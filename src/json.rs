@@ -0,0 +1,218 @@
+// the reader is only used by serve, which wasm builds leave out
+#![cfg_attr(target_arch = "wasm32", allow(dead_code))]
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Renders a flat JSON object with string values, preserving field order
+pub fn object(fields: &[(&str, String)]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("{}:{}", string(key), string(value)))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+/// Renders a JSON string literal, escaping quotes, backslashes and control characters
+pub fn string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// Json is a value of a flat JSON object: a string, or a number, boolean or null kept
+/// as written
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    String(String),
+    Literal(String),
+}
+
+impl Json {
+    /// Returns the string, or the literal as written
+    pub fn as_str(&self) -> &str {
+        match self {
+            Json::String(value) | Json::Literal(value) => value,
+        }
+    }
+
+    /// Renders the value back into JSON
+    pub fn render(&self) -> String {
+        match self {
+            Json::String(value) => string(value),
+            Json::Literal(value) => value.clone(),
+        }
+    }
+}
+
+/// Parses a flat JSON object, whose values are strings, numbers, booleans or null, into
+/// its fields in order. Returns None for anything else, including nested values.
+pub fn parse_object(text: &str) -> Option<Vec<(String, Json)>> {
+    let mut chars = text.trim().chars().peekable();
+    let mut fields = Vec::new();
+    if chars.next()? != '{' {
+        return None;
+    }
+    skip_whitespace(&mut chars);
+    if chars.peek() == Some(&'}') {
+        chars.next();
+        return chars.next().is_none().then_some(fields);
+    }
+
+    loop {
+        skip_whitespace(&mut chars);
+        if chars.next()? != '"' {
+            return None;
+        }
+        let key = parse_string(&mut chars)?;
+        skip_whitespace(&mut chars);
+        if chars.next()? != ':' {
+            return None;
+        }
+        skip_whitespace(&mut chars);
+        let value = if chars.peek() == Some(&'"') {
+            chars.next();
+            Json::String(parse_string(&mut chars)?)
+        } else {
+            let mut literal = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || "+-.".contains(*c)) {
+                literal.push(c);
+            }
+            let number = literal.parse::<f64>().is_ok() && !literal.starts_with('+');
+            if !number && !["true", "false", "null"].contains(&literal.as_str()) {
+                return None;
+            }
+            Json::Literal(literal)
+        };
+        fields.push((key, value));
+
+        skip_whitespace(&mut chars);
+        match chars.next()? {
+            ',' => continue,
+            '}' => break,
+            _ => return None,
+        }
+    }
+    chars.next().is_none().then_some(fields)
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+/// Reads the rest of a string literal after its opening quote, unescaping it
+fn parse_string(chars: &mut Peekable<Chars>) -> Option<String> {
+    let mut value = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(value),
+            '\\' => value.push(match chars.next()? {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'u' => parse_unicode_escape(chars)?,
+                c @ ('"' | '\\' | '/') => c,
+                _ => return None,
+            }),
+            c if (c as u32) < 0x20 => return None,
+            c => value.push(c),
+        }
+    }
+}
+
+/// Reads the four hex digits of a \u escape, and a second escape for the low half of a
+/// surrogate pair
+fn parse_unicode_escape(chars: &mut Peekable<Chars>) -> Option<char> {
+    let high = parse_hex4(chars)?;
+    if !(0xd800..0xdc00).contains(&high) {
+        return char::from_u32(high);
+    }
+    if chars.next()? != '\\' || chars.next()? != 'u' {
+        return None;
+    }
+    let low = parse_hex4(chars)?;
+    if !(0xdc00..0xe000).contains(&low) {
+        return None;
+    }
+    char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))
+}
+
+fn parse_hex4(chars: &mut Peekable<Chars>) -> Option<u32> {
+    let digits: String = chars.by_ref().take(4).collect();
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(value: &str) -> Json {
+        Json::Literal(value.to_string())
+    }
+
+    #[test]
+    fn parses_flat_objects() {
+        assert_eq!(parse_object(" {} ").unwrap(), []);
+        assert_eq!(
+            parse_object(r#"{"value": "0xff", "id": 7, "neg": -1.5e3, "ok": true, "none": null}"#)
+                .unwrap(),
+            [
+                ("value".to_string(), Json::String("0xff".to_string())),
+                ("id".to_string(), literal("7")),
+                ("neg".to_string(), literal("-1.5e3")),
+                ("ok".to_string(), literal("true")),
+                ("none".to_string(), literal("null")),
+            ]
+        );
+    }
+
+    #[test]
+    fn unescapes_strings() {
+        let fields = parse_object(r#"{"a": "q\"b\\s\/n\n\u00e9\ud83d\ude00"}"#).unwrap();
+        assert_eq!(
+            fields[0].1,
+            Json::String("q\"b\\s/n\n\u{e9}\u{1f600}".to_string())
+        );
+        assert_eq!(string("q\"b\\\n\u{1}"), r#""q\"b\\\n\u0001""#);
+    }
+
+    #[test]
+    fn rejects_malformed_objects() {
+        for text in [
+            "",
+            "[]",
+            "{",
+            r#"{"a": 1,}"#,
+            r#"{"a" 1}"#,
+            r#"{a: 1}"#,
+            r#"{"a": +1}"#,
+            r#"{"a": yes}"#,
+            r#"{"a": "bad \q escape"}"#,
+            r#"{"a": "\ud83d"}"#,
+            r#"{"a": "\u12"}"#,
+            "{\"a\": \"raw\nnewline\"}",
+            r#"{"a": {"b": 1}}"#,
+            r#"{"a": [1]}"#,
+            r#"{"a": 1} trailing"#,
+        ] {
+            assert!(parse_object(text).is_none(), "{:?}", text);
+        }
+    }
+}
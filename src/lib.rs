@@ -0,0 +1,1010 @@
+//! Conversion of numbers between bases, as used by the changebase command line tool.
+//!
+//! Values are parsed with [`Value::from`] in a given [`Base`], or in the base found by
+//! [`detect_base`], and written with [`Value::to_base`]. [`convert`] does both at once.
+//! [`run`] runs the command line tool for parsed [`Opt`]ions.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Read, Write};
+use std::str;
+use std::time::{Duration, Instant};
+
+pub mod opts;
+pub use opts::{Base, Opt};
+use opts::{Endian, Format, TableFormat, TimeUnit};
+use regex::Regex;
+mod address;
+mod align;
+pub mod base;
+mod bit;
+mod bytes;
+mod color;
+pub mod command;
+mod compare;
+pub mod config;
+mod defs;
+mod diagram;
+mod diff;
+use base::{
+    color_groups, default_group_size, digits_for_bits, explain_detection, group, pad, prefix, radix,
+};
+pub use base::{detect_base, DetectStrategy, Value};
+pub mod errors;
+pub use errors::BaseError;
+mod exotic;
+mod explain;
+mod expr;
+mod fields;
+#[cfg(not(target_arch = "wasm32"))]
+mod file;
+mod filter;
+mod flags;
+mod float;
+use float::{FloatBits, FloatFormat};
+mod fraction;
+mod hex;
+mod hexdump;
+use fraction::Fraction;
+mod info;
+mod json;
+mod keccak;
+mod literal;
+mod math;
+mod mnemonic;
+mod net;
+mod op;
+mod perm;
+mod prime;
+mod regex;
+mod repl;
+mod roman;
+mod seq;
+#[cfg(not(target_arch = "wasm32"))]
+mod serve;
+mod size;
+mod sort;
+mod stats;
+mod table;
+mod text;
+mod timestamp;
+#[cfg(not(target_arch = "wasm32"))]
+mod tui;
+mod unicode;
+mod uniq;
+mod units;
+mod uuid;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod widths;
+
+/// Number holds a parsed input value
+pub enum Number {
+    Integer(Value),
+    Fraction(Fraction),
+}
+
+/// Parsed holds a parsed input value along with how it was written
+pub struct Parsed {
+    pub base: Base,
+    pub num: Number,
+    /// Bit width given by a sized literal such as 8'hff
+    pub width: Option<usize>,
+    /// Whether a sized literal was marked as signed, e.g. 8'shff
+    pub signed: bool,
+}
+
+impl Parsed {
+    /// Returns the integer value, failing for fractional values
+    pub fn integer(&self) -> Result<&Value, BaseError> {
+        match &self.num {
+            Number::Integer(num) => Ok(num),
+            Number::Fraction(_) => Err(BaseError::ArgError {
+                message: "Expected an integer value, not a fraction",
+            }),
+        }
+    }
+
+    /// Replaces the value, keeping how it was written
+    pub fn with_value(self, value: Value) -> Parsed {
+        Parsed {
+            num: Number::Integer(value),
+            ..self
+        }
+    }
+}
+
+/// Converts the value from the given base, or its detected base, to the output base
+pub fn convert(value: &str, input: Option<Base>, output: Base) -> Result<String, BaseError> {
+    let input = match input {
+        Some(input) => input,
+        None => detect_base(value, DetectStrategy::Default)?,
+    };
+    Value::from(value, input)?.to_base(output)
+}
+
+/// Runs the command line tool, printing results to stdout
+pub fn run(opt: &Opt) -> Result<(), BaseError> {
+    opt.check_widths()?;
+    match &opt.cmd {
+        Some(cmd) => cmd.run(opt),
+        None if opt.values.is_empty() && opt.file.is_none() && io::stdin().is_terminal() => {
+            repl::run(opt)
+        }
+        None if opt.table == Some(TableFormat::Aligned) => {
+            let mut values = Vec::new();
+            for value in &opt.values {
+                match value.as_str() {
+                    "-" => values.extend(read_values(opt)?),
+                    value => values.push(value.to_string()),
+                }
+            }
+            if opt.values.is_empty() {
+                values = read_values(opt)?;
+            }
+            println!("{}", table::aligned(opt, &values)?);
+            Ok(())
+        }
+        None => {
+            if let Some(format) = &opt.table {
+                let mut columns = vec!["input".to_string()];
+                columns.extend(
+                    opt.output_bases()
+                        .iter()
+                        .map(|base| base.name().to_string()),
+                );
+                println!("{}", table::header(format, &columns));
+            }
+            let stdout = io::stdout();
+            let mut printer = Printer::new(opt, BufWriter::new(stdout.lock()))?;
+            let result = if opt.values.is_empty() {
+                convert_input(opt, &mut printer)
+            } else {
+                convert_args(opt, &mut printer)
+            };
+            printer.flush()?;
+            printer.report_time();
+            result?;
+            printer.summary()
+        }
+    }
+}
+
+/// Prints the error to stderr
+pub fn report_error(e: BaseError) {
+    match e {
+        BaseError::Input { input, error } => {
+            let position = match *error {
+                BaseError::InvalidDigit { position, .. }
+                | BaseError::UndetectedDigit { position, .. } => position,
+                _ => 0,
+            };
+            report_error(*error);
+            eprintln!("    {}", input);
+            eprintln!("    {:>width$}", "^", width = position + 1);
+        }
+        // the differences, or the exit code of a check, are the output
+        BaseError::Differs | BaseError::CheckFailed => {}
+        e => eprintln!("{}", error_message(&e)),
+    }
+}
+
+/// Describes the error in a line, as it is reported on stderr
+pub fn error_message(e: &BaseError) -> String {
+    match e {
+        BaseError::EmptyInput
+        | BaseError::InvalidDigit { .. }
+        | BaseError::InvalidPrefix { .. }
+        | BaseError::AmbiguousBase
+        | BaseError::NoValidBase
+        | BaseError::UndetectedDigit { .. }
+        | BaseError::InvalidFormat { .. }
+        | BaseError::InvalidSyntax { .. }
+        | BaseError::InvalidChecksum => format!("Error parsing value: {}", e),
+        BaseError::Input { error, .. } => error_message(error),
+        BaseError::ArgError { message } => format!("Invalid arguments: {}", message),
+        BaseError::OverflowError { message } => format!("Value out of range: {}", message),
+        BaseError::Overflow { bits_required } => {
+            format!("Value out of range: value requires {} bits", bits_required)
+        }
+        BaseError::EvalError { message } => format!("Error evaluating expression: {}", message),
+        BaseError::BatchFailure { .. } => format!("Error converting values: {}", e),
+        BaseError::IoError { message } => format!("I/O error: {}", message),
+        BaseError::Differs | BaseError::CheckFailed => e.to_string(),
+    }
+}
+
+/// Prints the detected base of the value, or with --explain why it was chosen and which
+/// other bases the value is valid in
+fn detect(opt: &Opt, value: &str) -> Result<(), BaseError> {
+    let base = detect_base(value, opt.detect_strategy()).map_err(|e| e.in_input(value))?;
+    if opt.explain {
+        println!("{}", explain_detection(value, base));
+    } else {
+        println!("{}", base.name());
+    }
+    Ok(())
+}
+
+/// Prints the value as read in each base it is valid in
+fn interpret(opt: &Opt, value: &str) -> Result<(), BaseError> {
+    let output = opt.get_output().unwrap_or(Base::Dec);
+    let mut valid = false;
+    for base in opt.shown_bases() {
+        if let Ok(num) = Value::from(value, base.clone()) {
+            let parsed = Parsed {
+                base: base.clone(),
+                num: Number::Integer(num),
+                width: None,
+                signed: false,
+            };
+            println!(
+                "{}: {}",
+                base.name(),
+                format_number(opt, &parsed, output.clone())?
+            );
+            valid = true;
+        }
+    }
+
+    if valid {
+        Ok(())
+    } else {
+        Err(BaseError::NoValidBase)
+    }
+}
+
+/// Printer writes one conversion result per line, or per NUL-terminated record with
+/// --print0. With --no-newline, the terminator after the last result is left out.
+/// With --keep-going, values that fail are reported and counted rather than stopping
+/// the batch. With --extract, only the numbers matching the pattern are converted.
+struct Printer<W: Write> {
+    writer: W,
+    extract: Option<Regex>,
+    terminator: char,
+    no_newline: bool,
+    printed: bool,
+    keep_going: bool,
+    total: usize,
+    failed: usize,
+    time: bool,
+    started: Instant,
+    parse_time: Duration,
+    convert_time: Duration,
+}
+
+impl<W: Write> Printer<W> {
+    fn new(opt: &Opt, writer: W) -> Result<Printer<W>, BaseError> {
+        Ok(Printer {
+            writer,
+            extract: opt.extract.as_deref().map(Regex::new).transpose()?,
+            terminator: if opt.print0 { '\0' } else { '\n' },
+            no_newline: opt.no_newline,
+            printed: false,
+            keep_going: opt.keep_going,
+            total: 0,
+            failed: 0,
+            time: opt.time,
+            started: Instant::now(),
+            parse_time: Duration::ZERO,
+            convert_time: Duration::ZERO,
+        })
+    }
+
+    /// Converts the text as one value, or with --extract, each number in it that
+    /// matches the pattern
+    fn convert_text(&mut self, opt: &Opt, text: &str) -> Result<(), BaseError> {
+        let spans: Vec<(usize, usize)> = match &self.extract {
+            Some(pattern) => pattern
+                .find_all(text)
+                .iter()
+                .filter_map(|found| found.first_group())
+                .collect(),
+            None => return self.convert(opt, text),
+        };
+        for (start, end) in spans {
+            self.convert(opt, &text[start..end])?;
+        }
+        Ok(())
+    }
+
+    /// Converts and prints the value. I/O errors always stop the batch.
+    fn convert(&mut self, opt: &Opt, value: &str) -> Result<(), BaseError> {
+        self.total += 1;
+        let start = Instant::now();
+        let mut parse_time = Duration::ZERO;
+        let converted = convert_base(opt, value, &mut parse_time).map_err(|e| e.in_input(value));
+        let elapsed = start.elapsed();
+        opt.trace(3, || format!("Converted {} in {:?}", value, elapsed));
+        if self.time {
+            // values are numbered rather than repeated, as they may be megabytes long
+            let convert_time = elapsed.saturating_sub(parse_time);
+            eprintln!(
+                "Time for value {}: parse {:?}, convert {:?}, total {:?}",
+                self.total, parse_time, convert_time, elapsed
+            );
+            self.parse_time += parse_time;
+            self.convert_time += convert_time;
+        }
+        match converted {
+            Ok(converted) => self.print(&converted),
+            Err(e) if self.keep_going && !matches!(e, BaseError::IoError { .. }) => {
+                self.failed += 1;
+                self.flush()?;
+                report_error(e);
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Prints the time taken by the whole batch with --time, if it had more than one value
+    fn report_time(&self) {
+        if self.time && self.total > 1 {
+            eprintln!(
+                "Time for {} values: parse {:?}, convert {:?}, total {:?}",
+                self.total,
+                self.parse_time,
+                self.convert_time,
+                self.started.elapsed()
+            );
+        }
+    }
+
+    /// Returns the summary error if any value of the batch failed
+    fn summary(&self) -> Result<(), BaseError> {
+        if self.failed > 0 {
+            Err(BaseError::BatchFailure {
+                failed: self.failed,
+                total: self.total,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn print(&mut self, result: &str) -> Result<(), BaseError> {
+        let written = if !self.no_newline {
+            write!(self.writer, "{}{}", result, self.terminator)
+        } else if self.printed {
+            write!(self.writer, "{}{}", self.terminator, result)
+        } else {
+            write!(self.writer, "{}", result)
+        };
+        self.printed = true;
+        written.map_err(|_| BaseError::IoError {
+            message: "Unable to write to stdout",
+        })
+    }
+
+    fn flush(&mut self) -> Result<(), BaseError> {
+        self.writer.flush().map_err(|_| BaseError::IoError {
+            message: "Unable to write to stdout",
+        })
+    }
+}
+
+/// Converts each value given on the command line, reading stdin in place of `-`
+fn convert_args<W: Write>(opt: &Opt, printer: &mut Printer<W>) -> Result<(), BaseError> {
+    for value in &opt.values {
+        if value == "-" {
+            convert_reader(opt, io::stdin().lock(), false, printer)?;
+        } else {
+            printer.convert_text(opt, value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Opens the file given with --file, or stdin for `-`
+fn open_file(path: &str) -> Result<Box<dyn BufRead>, BaseError> {
+    if path == "-" {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    let file = File::open(path).map_err(|_| BaseError::IoError {
+        message: "Unable to read input file",
+    })?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+/// Splits a line of input into values: the whole trimmed line when reading a file,
+/// otherwise each whitespace-separated word
+fn line_values(line: &str, per_line: bool) -> impl Iterator<Item = &str> {
+    let whole = Some(line.trim()).filter(|value| per_line && !value.is_empty());
+    let words = line.split_whitespace().filter(move |_| !per_line);
+    whole.into_iter().chain(words)
+}
+
+/// Reads all values from stdin, or from the --file
+fn read_values(opt: &Opt) -> Result<Vec<String>, BaseError> {
+    let (mut reader, per_line) = match &opt.file {
+        Some(path) => (open_file(path)?, true),
+        None => (Box::new(io::stdin().lock()) as Box<dyn BufRead>, false),
+    };
+    let mut text = String::new();
+    reader
+        .read_to_string(&mut text)
+        .map_err(|_| BaseError::IoError {
+            message: "Unable to read input",
+        })?;
+    Ok(text
+        .lines()
+        .flat_map(|line| line_values(line, per_line))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Reads the values from stdin, or from the --file, parsing each as an integer
+fn read_numbers(opt: &Opt) -> Result<Vec<(String, Value)>, BaseError> {
+    read_values(opt)?
+        .into_iter()
+        .map(|value| {
+            let num = parse_number(opt, &value, None)
+                .and_then(|parsed| parsed.integer().cloned())
+                .map_err(|e| e.in_input(&value))?;
+            Ok((value, num))
+        })
+        .collect()
+}
+
+/// Formats an integer that was computed rather than read, in the output base
+fn format_integer(opt: &Opt, num: Value, output: Base) -> Result<String, BaseError> {
+    let parsed = Parsed {
+        base: Base::Dec,
+        num: Number::Integer(num),
+        width: None,
+        signed: false,
+    };
+    format_number(opt, &parsed, output)
+}
+
+/// Converts the values read from stdin, or from the --file if given
+fn convert_input<W: Write>(opt: &Opt, printer: &mut Printer<W>) -> Result<(), BaseError> {
+    match &opt.file {
+        Some(path) => convert_stream(opt, open_file(path)?, true, printer),
+        None => convert_stream(opt, io::stdin().lock(), false, printer),
+    }
+}
+
+/// Converts the values read, or the whole input as one value if it is a dump, as
+/// with `-i hexdump` or when its first line is an xxd or hexdump -C line
+fn convert_stream<R: BufRead, W: Write>(
+    opt: &Opt,
+    mut reader: R,
+    per_line: bool,
+    printer: &mut Printer<W>,
+) -> Result<(), BaseError> {
+    let dump = match opt.explicit_input() {
+        Some(base) => base == Base::Hexdump,
+        // the first line is looked at without reading it
+        None if !opt.stdin0 => reader
+            .fill_buf()
+            .map(|buffer| {
+                let line = buffer.split(|byte| *byte == b'\n').next().unwrap_or(&[]);
+                hexdump::is_dump_line(&String::from_utf8_lossy(line))
+            })
+            .unwrap_or(false),
+        None => false,
+    };
+    if !dump {
+        return convert_reader(opt, reader, per_line, printer);
+    }
+
+    // a dump spans many lines, so the whole input is one value
+    let mut dump = String::new();
+    reader
+        .read_to_string(&mut dump)
+        .map_err(|_| BaseError::IoError {
+            message: "Unable to read input",
+        })?;
+    printer.convert(opt, &dump)
+}
+
+/// Converts each value read, either one per line or whitespace-separated, or each
+/// NUL-terminated record with --stdin0, printing one result per value
+fn convert_reader<R: BufRead, W: Write>(
+    opt: &Opt,
+    mut reader: R,
+    per_line: bool,
+    printer: &mut Printer<W>,
+) -> Result<(), BaseError> {
+    if opt.stdin0 {
+        let mut record = Vec::new();
+        loop {
+            record.clear();
+            let read = reader
+                .read_until(b'\0', &mut record)
+                .map_err(|_| BaseError::IoError {
+                    message: "Unable to read input",
+                })?;
+            if read == 0 {
+                return Ok(());
+            }
+            if record.last() == Some(&b'\0') {
+                record.pop();
+            }
+            if record.is_empty() {
+                continue;
+            }
+
+            let value = str::from_utf8(&record).map_err(|_| BaseError::IoError {
+                message: "Records read must be UTF-8",
+            })?;
+            printer.convert_text(opt, value)?;
+        }
+    }
+
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|_| BaseError::IoError {
+                message: "Unable to read input",
+            })?;
+        if read == 0 {
+            break;
+        }
+
+        // patterns are matched against whole lines, without the line ending
+        if printer.extract.is_some() {
+            printer.convert_text(opt, line.trim_end_matches(&['\n', '\r'][..]))?;
+            continue;
+        }
+        for value in line_values(&line, per_line) {
+            printer.convert(opt, value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Splits a base name annotation, such as hex: in hex:ff, from the value
+fn annotated_base(value: &str) -> Option<(Base, &str)> {
+    let (name, digits) = value.split_once(':')?;
+    Some((name.parse().ok()?, digits))
+}
+
+/// Converts the value, adding the time spent parsing it to `parse_time`
+fn convert_base(opt: &Opt, value: &str, parse_time: &mut Duration) -> Result<String, BaseError> {
+    if let Some((base, digits)) = annotated_base(value) {
+        opt.trace(2, || {
+            format!("Reading {} as {} from its annotation", digits, base.repr())
+        });
+        let mut opt = opt.clone();
+        opt.input = Some(base);
+        return convert_base(&opt, digits, parse_time).map_err(|e| e.in_input(digits));
+    }
+    if let Some(format) = opt.float_format(value) {
+        opt.trace(2, || {
+            format!("Inspecting {} as a {}-bit float", value, format.width())
+        });
+        return inspect_float(opt, value, format);
+    }
+
+    let start = Instant::now();
+    let parsed = parse_number(opt, value, None);
+    *parse_time += start.elapsed();
+    let mut parsed = parsed?;
+    if opt.modulus.is_some() {
+        let num = op::reduce(opt, parsed.integer()?.clone())?;
+        parsed = parsed.with_value(num);
+    }
+    if opt.next_pow2 {
+        let num = align::next_power_of_two(parsed.integer()?)?;
+        parsed = parsed.with_value(num);
+    }
+    if let Some(width) = opt.swap_bytes {
+        parsed = swap_bytes(opt, parsed, width)?;
+    }
+
+    if let Some(ty) = &opt.fits {
+        widths::check_fits(opt, &parsed, ty)?;
+    }
+
+    if let Some(answer) = info::query(opt, &parsed)? {
+        return Ok(answer);
+    }
+
+    if opt.explain {
+        return explain::explain(opt, &parsed);
+    }
+    if opt.places {
+        return explain::place_values(&parsed);
+    }
+
+    if opt.diagram {
+        let width = opt.width.or(parsed.width);
+        let num = info::unsigned(parsed.integer()?, width)?;
+        let width = width.unwrap_or(num.bits().div_ceil(8) as usize * 8);
+        return Ok(diagram::render(&num, width, io::stdout().is_terminal()));
+    }
+
+    if !opt.mixed_radix.is_empty() {
+        if opt.mixed_radix.iter().any(|radix| *radix < 2) {
+            return Err(BaseError::ArgError {
+                message: "Mixed radices must be at least 2",
+            });
+        }
+        let num = parsed.integer()?.clone().into_inner();
+        return Ok(exotic::to_mixed_radix(&num, &opt.mixed_radix));
+    }
+
+    if opt.mnemonic {
+        return mnemonic::encode(opt, &parsed);
+    }
+
+    if opt.widths {
+        return widths::table(opt, &parsed);
+    }
+
+    if let Some(spec) = &opt.fields {
+        return fields::decode(opt, &parsed, spec);
+    }
+
+    if let Some(spec) = &opt.flags {
+        return flags::decode(opt, &parsed, spec);
+    }
+
+    if opt.bytes.is_some() {
+        return format_bytes(opt, &parsed);
+    }
+
+    if opt.chunk == Some(0) {
+        return Err(BaseError::ArgError {
+            message: "--chunk must be at least one byte",
+        });
+    }
+    if opt.chunk.is_some() && opt.get_output() != Some(Base::Hex) {
+        return Err(BaseError::ArgError {
+            message: "--chunk splits hex output, use it with --oh",
+        });
+    }
+
+    if let Some(format) = &opt.table {
+        let mut cells = vec![value.to_string()];
+        for base in opt.output_bases() {
+            cells.push(format_number(opt, &parsed, base)?);
+        }
+        return Ok(table::row(format, &cells));
+    }
+
+    if opt.json {
+        let mut fields = vec![
+            ("input", value.to_string()),
+            ("base", parsed.base.to_string()),
+        ];
+        match opt.get_output() {
+            Some(output) => {
+                fields.push(("output", output.to_string()));
+                fields.push(("value", format_number(opt, &parsed, output)?));
+            }
+            None => {
+                for base in opt.shown_bases() {
+                    fields.push((base.name(), format_number(opt, &parsed, base)?));
+                }
+                if let Some(signed) = signed_decimal(opt, &parsed)? {
+                    fields.push(("signed", signed));
+                }
+            }
+        }
+        if let Some(datetime) = datetime(opt, &parsed)? {
+            fields.push(("utc", datetime));
+        }
+        Ok(json::object(&fields))
+    } else {
+        let output = format_output(opt, &parsed)?;
+        match datetime(opt, &parsed)? {
+            Some(datetime) => Ok(format!("{}\nutc: {}", output, datetime)),
+            None => Ok(output),
+        }
+    }
+}
+
+/// Returns the UTC datetime of the value for --as-timestamp
+fn datetime(opt: &Opt, parsed: &Parsed) -> Result<Option<String>, BaseError> {
+    match &opt.as_timestamp {
+        Some(unit) => {
+            let unit = unit.clone().unwrap_or(TimeUnit::S);
+            let num = parsed.integer()?.clone().into_inner();
+            timestamp::to_datetime(&num, &unit).map(Some)
+        }
+        None => Ok(None),
+    }
+}
+
+/// Renders the number in the output base, or in every base if none is given
+fn format_output(opt: &Opt, parsed: &Parsed) -> Result<String, BaseError> {
+    match opt.get_output() {
+        Some(output) => format_number(opt, parsed, output),
+        None => format_all_bases(opt, parsed),
+    }
+}
+
+/// Parses a single value, sized literal or expression.
+/// `last` is the value bound to `_` and `last` in expressions.
+fn parse_number(opt: &Opt, value: &str, last: Option<&Value>) -> Result<Parsed, BaseError> {
+    // numerals and formats such as XL or rwxr-xr-x are never literals or expressions
+    if let Some(input) = opt
+        .explicit_input()
+        .filter(|base| radix(base.clone()).is_none())
+    {
+        let num = match input {
+            Base::Size => Value::new(size::parse(value, opt.si)?),
+            _ => Value::from(value, input.clone())?,
+        };
+        return Ok(Parsed {
+            base: input,
+            num: Number::Integer(num),
+            width: None,
+            signed: false,
+        });
+    }
+
+    // dumps are checked first, as their ASCII gutter may hold anything
+    if opt.explicit_input().is_none() && hexdump::is_hexdump(value) {
+        let input = opt.get_input(value)?;
+        let num = Value::from(value, input.clone())?;
+        return Ok(Parsed {
+            base: input,
+            num: Number::Integer(num),
+            width: None,
+            signed: false,
+        });
+    }
+
+    if let Some(literal) = literal::parse_verilog(value)? {
+        opt.trace(1, || {
+            format!("Converting {} from {}", value, literal.base.repr())
+        });
+        let num = Value::from(&literal.digits, literal.base.clone())?;
+        return Ok(Parsed {
+            base: literal.base,
+            num: Number::Integer(num),
+            width: literal.width,
+            signed: literal.signed,
+        });
+    }
+
+    if expr::is_expression(value) || expr::is_variable(value) {
+        let explicit = opt.explicit_input();
+        let input = explicit.clone().unwrap_or(Base::Dec);
+        opt.trace(1, || format!("Evaluating {} in {}", value, input.repr()));
+        let num = expr::evaluate(value, explicit, last)?;
+        return Ok(Parsed {
+            base: input,
+            num: Number::Integer(num),
+            width: None,
+            signed: false,
+        });
+    }
+
+    let value = &literal::strip_code_literal(value);
+    let input = match opt.explicit_input() {
+        None if opt.c_octal && literal::is_c_octal(value) => Base::Oct,
+        _ => opt.get_input(value)?,
+    };
+    opt.trace(1, || format!("Converting {} from {}", value, input.repr()));
+
+    if Fraction::is_fractional(value) {
+        if opt.width.is_some() {
+            return Err(BaseError::ArgError {
+                message: "--width cannot be used with fractional values",
+            });
+        }
+        let num = Fraction::from(value, input.clone())?;
+        Ok(Parsed {
+            base: input,
+            num: Number::Fraction(num),
+            width: None,
+            signed: false,
+        })
+    } else {
+        let num = Value::from(value, input.clone())?;
+        Ok(Parsed {
+            base: input,
+            num: Number::Integer(num),
+            width: None,
+            signed: false,
+        })
+    }
+}
+
+fn format_number(opt: &Opt, parsed: &Parsed, output: Base) -> Result<String, BaseError> {
+    let width = opt.width.or(parsed.width);
+    let signed = opt.signed || parsed.signed;
+    let digits = match &parsed.num {
+        Number::Integer(num) if opt.vinculum && output == Base::Roman => {
+            roman::to_roman(&num.clone().into_inner(), true)?
+        }
+        Number::Integer(num) if opt.ip_hex && output == Base::Ip4 => {
+            net::to_ip4(&num.clone().into_inner(), true)?
+        }
+        Number::Integer(num) if output == Base::Size => {
+            size::to_size(&num.clone().into_inner(), opt.si)?
+        }
+        // --width is the number of bytes per line, rather than a bit width
+        Number::Integer(num) if output == Base::Hexdump => match opt.width {
+            Some(0) => {
+                return Err(BaseError::ArgError {
+                    message: "Hexdump width must be at least one byte",
+                })
+            }
+            width => hexdump::to_hexdump(num, width.unwrap_or(hexdump::DEFAULT_WIDTH)),
+        },
+        Number::Integer(num) => format_value(num, width, signed, output.clone())?,
+        Number::Fraction(num) => num.to_base(output.clone(), opt.precision)?,
+    };
+    let digits = match output {
+        Base::Doz => exotic::style_dozenal(&digits, &opt.doz_digits),
+        Base::Hex if opt.upper => digits.to_uppercase(),
+        _ => digits,
+    };
+
+    let len = opt.pad.unwrap_or(0).max(
+        opt.bits
+            .map(|bits| digits_for_bits(output.clone(), bits))
+            .unwrap_or(0),
+    );
+    let len = match output {
+        // ABI words are 32 bytes, so longer values fill whole words
+        Base::Hex if opt.pad_word => len.max(digits.len().div_ceil(64).max(1) * 64),
+        _ => len,
+    };
+    let digits = pad(&digits, len);
+
+    match opt.chunk {
+        Some(bytes) if output == Base::Hex => {
+            let digits = pad(&digits, digits.len().div_ceil(2) * 2);
+            let lines = digits
+                .as_bytes()
+                .chunks(bytes * 2)
+                .enumerate()
+                .map(|(index, line)| {
+                    let line = String::from_utf8_lossy(line);
+                    let line = decorate(opt, parsed, &line, &output, width, signed)?;
+                    Ok(format!("{:04x}: {}", index * bytes, line))
+                })
+                .collect::<Result<Vec<String>, BaseError>>()?;
+            Ok(lines.join("\n"))
+        }
+        _ => decorate(opt, parsed, &digits, &output, width, signed),
+    }
+}
+
+/// Groups the digits and adds the prefix, suffix or literal syntax of the output format
+fn decorate(
+    opt: &Opt,
+    parsed: &Parsed,
+    digits: &str,
+    output: &Base,
+    width: Option<usize>,
+    signed: bool,
+) -> Result<String, BaseError> {
+    // languages with digit separators get underscore grouping by default
+    let separated = opt.format.as_ref().is_some_and(Format::allows_separators);
+    let separator = if separated { "_" } else { &opt.separator };
+    let colored = opt.color_groups() && opt.format.is_none();
+    let digits = match (opt.group, output) {
+        (Some(size), Base::Bin) | (Some(size), Base::Hex) if colored => {
+            let size = size.unwrap_or_else(|| default_group_size(output));
+            color_groups(digits, size, separator)
+        }
+        (Some(size), _) => {
+            let size = size.unwrap_or_else(|| default_group_size(output));
+            group(digits, size, separator)
+        }
+        (None, _) if separated => group(digits, default_group_size(output), separator),
+        // nibbles of binary and bytes of hex
+        (None, Base::Bin) if colored => color_groups(digits, 4, ""),
+        (None, Base::Hex) if colored => color_groups(digits, 2, ""),
+        (None, _) => digits.to_string(),
+    };
+
+    let suffix = opt.type_suffix.as_deref().unwrap_or("");
+    match (&opt.format, &parsed.num) {
+        (Some(Format::Verilog), Number::Integer(num)) => {
+            let width = width.unwrap_or(num.bits() as usize);
+            literal::to_verilog(&digits, output, width, signed)
+        }
+        (Some(format), _) if format.prefix_style().is_some() => {
+            let style = format.prefix_style().unwrap();
+            Ok(prefix(&digits, output.prefix(&style)) + suffix)
+        }
+        _ => match opt.get_prefix_style() {
+            Some(style) => Ok(prefix(&digits, output.prefix(&style))),
+            None => Ok(digits),
+        },
+    }
+}
+
+fn format_value(
+    num: &Value,
+    width: Option<usize>,
+    signed: bool,
+    output: Base,
+) -> Result<String, BaseError> {
+    match width {
+        Some(width) => {
+            let num = if signed {
+                num.to_signed(width)?
+            } else {
+                num.to_unsigned(width)?
+            };
+            num.to_padded_base(output, width)
+        }
+        None => num.to_base(output),
+    }
+}
+
+/// Reverses the byte order of the number. Without an explicit width, the --width or
+/// literal width is used, falling back to the smallest power of two bytes that holds it.
+fn swap_bytes(opt: &Opt, parsed: Parsed, width: Option<usize>) -> Result<Parsed, BaseError> {
+    let num = parsed.integer()?;
+
+    let width = match width.or(opt.width).or(parsed.width) {
+        Some(width) => width,
+        None => (num.bits() as usize).div_ceil(8).next_power_of_two() * 8,
+    };
+    let num = num.swap_bytes(width)?;
+    Ok(Parsed {
+        width: Some(width),
+        ..parsed.with_value(num)
+    })
+}
+
+/// Renders the bytes of the number, padded to the bit width if one is given
+fn format_bytes(opt: &Opt, parsed: &Parsed) -> Result<String, BaseError> {
+    let num = parsed.integer()?;
+
+    let bytes = match opt.width.or(parsed.width) {
+        Some(width) => {
+            let mut bytes = num.to_unsigned(width)?.to_bytes(Endian::Le);
+            bytes.resize(width.div_ceil(8), 0);
+            if opt.endian == Endian::Be {
+                bytes.reverse();
+            }
+            bytes
+        }
+        None => num.to_bytes(opt.endian.clone()),
+    };
+    Ok(bytes::format_bytes(&bytes, opt.bytes.as_ref().unwrap()))
+}
+
+/// Renders the number in every base, one labelled line per base
+fn format_all_bases(opt: &Opt, parsed: &Parsed) -> Result<String, BaseError> {
+    let mut lines = opt
+        .shown_bases()
+        .into_iter()
+        .map(|base| {
+            Ok(format!(
+                "{}: {}",
+                base.name(),
+                format_number(opt, parsed, base)?
+            ))
+        })
+        .collect::<Result<Vec<String>, BaseError>>()?;
+    if let Some(signed) = signed_decimal(opt, parsed)? {
+        lines.push(format!("signed: {}", signed));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Returns the two's complement decimal reading of an integer with a width, unless
+/// the decimal output is already signed
+fn signed_decimal(opt: &Opt, parsed: &Parsed) -> Result<Option<String>, BaseError> {
+    match (&parsed.num, opt.width.or(parsed.width)) {
+        (Number::Integer(num), Some(width)) if !(opt.signed || parsed.signed) => {
+            Ok(Some(num.to_signed(width)?.to_base(Base::Dec)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn inspect_float(opt: &Opt, value: &str, format: FloatFormat) -> Result<String, BaseError> {
+    let bits = if opt.float_opts.as_float {
+        let input = opt.explicit_input().unwrap_or(Base::Hex);
+        FloatBits::from_bits(value, input, format)?
+    } else {
+        FloatBits::from_float(value, format)?
+    };
+    Ok(bits.describe())
+}
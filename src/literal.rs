@@ -0,0 +1,129 @@
+use crate::errors::{BaseError, Syntax};
+use crate::opts::Base;
+use std::borrow::Cow;
+
+/// Literal holds the parts of a sized literal such as Verilog's `8'hff`
+pub struct Literal {
+    pub digits: String,
+    pub base: Base,
+    pub width: Option<usize>,
+    pub signed: bool,
+}
+
+/// Rust integer type suffixes, longest first so `u128` is not mistaken for `u8`
+const TYPE_SUFFIXES: [&str; 12] = [
+    "usize", "isize", "u128", "i128", "u64", "i64", "u32", "i32", "u16", "i16", "u8", "i8",
+];
+
+/// Strips the digit separators and type suffixes that C and Rust allow on integer literals,
+/// e.g. `0xFF_FFu32` becomes `0xFFFF` and `255UL` becomes `255`
+pub fn strip_code_literal(value: &str) -> Cow<'_, str> {
+    if value.contains('_') {
+        let value: String = value.chars().filter(|c| *c != '_').collect();
+        Cow::Owned(strip_type_suffix(&value).to_string())
+    } else {
+        Cow::Borrowed(strip_type_suffix(value))
+    }
+}
+
+fn strip_type_suffix(value: &str) -> &str {
+    for suffix in TYPE_SUFFIXES.iter() {
+        match value.strip_suffix(suffix) {
+            Some(digits) if !digits.is_empty() => return digits,
+            _ => {}
+        }
+    }
+
+    // C allows up to three of u, l and ll in either order, e.g. 10ULL or 10lu
+    let digits = value.trim_end_matches(&['u', 'U', 'l', 'L'][..]);
+    if !digits.is_empty() && value.len() - digits.len() <= 3 {
+        digits
+    } else {
+        value
+    }
+}
+
+/// Returns true if the value is a C-style octal literal with a leading zero, e.g. `0755`
+pub fn is_c_octal(value: &str) -> bool {
+    let digits = value.trim_start_matches('-');
+    digits.len() > 1 && digits.starts_with('0') && digits.chars().all(|c| ('0'..='7').contains(&c))
+}
+
+/// Parses a Verilog or SystemVerilog literal such as `8'hFF`, `16'b1010_1010`, `32'sd255` or `'hff`.
+/// Returns None if the value is not written in that syntax.
+pub fn parse_verilog(value: &str) -> Result<Option<Literal>, BaseError> {
+    let (sign, value) = match value.strip_prefix('-') {
+        Some(value) => ("-", value),
+        None => ("", value),
+    };
+    let (width, rest) = match value.split_once('\'') {
+        Some(parts) => parts,
+        None => return Ok(None),
+    };
+
+    let width = if width.is_empty() {
+        None
+    } else {
+        match width.parse::<usize>() {
+            Ok(width) if width > 0 => Some(width),
+            _ => return Err(get_parse_error()),
+        }
+    };
+
+    let (signed, rest) = match rest.strip_prefix(&['s', 'S'][..]) {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    let mut chars = rest.chars();
+    let base = match chars.next().map(|c| c.to_ascii_lowercase()) {
+        Some('b') => Base::Bin,
+        Some('o') => Base::Oct,
+        Some('d') => Base::Dec,
+        Some('h') => Base::Hex,
+        _ => return Err(get_parse_error()),
+    };
+
+    let digits: String = chars.filter(|c| *c != '_').collect();
+    if digits.is_empty() {
+        return Err(get_parse_error());
+    }
+
+    Ok(Some(Literal {
+        digits: format!("{}{}", sign, digits),
+        base,
+        width,
+        signed,
+    }))
+}
+
+/// Renders digits in the given base as a sized Verilog literal, e.g. `8'hff`
+pub fn to_verilog(
+    digits: &str,
+    base: &Base,
+    width: usize,
+    signed: bool,
+) -> Result<String, BaseError> {
+    let letter = match base {
+        Base::Bin => 'b',
+        Base::Oct => 'o',
+        Base::Dec => 'd',
+        Base::Hex => 'h',
+        _ => {
+            return Err(BaseError::ArgError {
+                message: "Verilog literals can only be written in binary, octal, decimal or hex",
+            })
+        }
+    };
+    let (sign, digits) = match digits.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", digits),
+    };
+    let signed = if signed { "s" } else { "" };
+    Ok(format!("{}{}'{}{}{}", sign, width, signed, letter, digits))
+}
+
+fn get_parse_error() -> BaseError {
+    BaseError::InvalidSyntax {
+        syntax: Syntax::Verilog,
+    }
+}
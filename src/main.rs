@@ -1,42 +1,11 @@
-use structopt::StructOpt;
-
-mod opts;
-use opts::Opt;
-mod base;
-use base::Value;
-mod errors;
-use errors::BaseError;
+use changebase::{config, report_error, run, Opt};
+use std::process;
 
 fn main() {
-    let opt = Opt::from_args();
-
-    let result = convert_base(opt);
-    if let Ok(val) = result {
-        println!("{}", val);
-    } else if let Err(e) = result {
-        match e {
-            BaseError::ParseError { message } => {
-                eprintln!("Error parsing value: {}", message)
-            }
-            BaseError::ArgError { message } => {
-                eprintln!("Invalid arguments: {}", message)
-            }
-        }
-    }
-}
-
-fn convert_base(opt: Opt) -> Result<String, BaseError> {
-    let input = opt.get_input()?;
-    let output = opt.get_output()?;
-    if opt.verbose {
-        println!(
-            "Converting {} from {} to {}",
-            &opt.value,
-            input.repr(),
-            output.repr()
-        );
+    let mut opt = Opt::from_args_with_suggestions();
+    if let Err(e) = config::apply(&mut opt).and_then(|_| run(&opt)) {
+        let code = e.exit_code();
+        report_error(e);
+        process::exit(code);
     }
-
-    let num = Value::from(opt.value, input)?;
-    Ok(num.to_base(output))
 }
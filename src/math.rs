@@ -0,0 +1,38 @@
+use crate::base::Value;
+use crate::command::MathOp;
+use crate::errors::BaseError;
+use crate::opts::Opt;
+use crate::{format_output, parse_number};
+use num::{Integer, Signed};
+
+/// Runs a number theory function, printing the result in the output base
+pub fn run(opt: &Opt, op: &MathOp) -> Result<(), BaseError> {
+    let read = |value: &str| parse_number(opt, value, None).map_err(|e| e.in_input(value));
+    let (parsed, result) = match op {
+        MathOp::Gcd { a, b } | MathOp::Lcm { a, b } => {
+            let (lhs, rhs) = (read(a)?, read(b)?);
+            let x = lhs.integer()?.clone().into_inner();
+            let y = rhs.integer()?.clone().into_inner();
+            let result = match op {
+                MathOp::Gcd { .. } => x.gcd(&y),
+                _ => x.lcm(&y),
+            };
+            (lhs, result)
+        }
+        MathOp::Isqrt { value } => {
+            let parsed = read(value)?;
+            let num = parsed.integer()?.clone().into_inner();
+            if num.is_negative() {
+                return Err(BaseError::ArgError {
+                    message: "isqrt needs a non-negative value",
+                });
+            }
+            (parsed, num.sqrt())
+        }
+    };
+    println!(
+        "{}",
+        format_output(opt, &parsed.with_value(Value::new(result)))?
+    );
+    Ok(())
+}
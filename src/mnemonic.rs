@@ -0,0 +1,126 @@
+use crate::base::Value;
+use crate::errors::{BaseError, Syntax};
+use crate::info::unsigned;
+use crate::opts::{Base, Opt};
+use crate::{format_output, Number, Parsed};
+use num::{bigint::BigInt, ToPrimitive, Zero};
+use std::fs;
+
+/// Number of words in a wordlist, so each word holds 11 bits
+const WORDS: usize = 2048;
+const WORD_BITS: usize = 11;
+
+/// The BIP39 English wordlist, used unless --wordlist names another
+const ENGLISH: &str = include_str!("english.txt");
+
+/// Reads a wordlist of 2048 distinct words, one per line, from the file if one is given,
+/// or else returns the BIP39 English list
+pub fn load_wordlist(path: Option<&str>) -> Result<Vec<String>, BaseError> {
+    let text = match path {
+        Some(path) => fs::read_to_string(path).map_err(|_| BaseError::IoError {
+            message: "Unable to read wordlist",
+        })?,
+        None => ENGLISH.to_string(),
+    };
+    let words: Vec<String> = text
+        .lines()
+        .map(|line| line.trim().to_lowercase())
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    let mut sorted = words.clone();
+    sorted.sort();
+    sorted.dedup();
+    if words.len() != WORDS || sorted.len() != WORDS {
+        return Err(BaseError::ArgError {
+            message: "Wordlist must contain 2048 distinct words, one per line",
+        });
+    }
+    Ok(words)
+}
+
+/// Writes the value as words, eleven bits per word with the most significant first. With
+/// a width, enough words are written to hold every bit of it.
+pub fn encode(opt: &Opt, parsed: &Parsed) -> Result<String, BaseError> {
+    let words = load_wordlist(opt.wordlist.as_deref())?;
+    let width = opt.width.or(parsed.width);
+    let mut num = unsigned(parsed.integer()?, width)?.into_inner();
+    let count = width.unwrap_or(0).div_ceil(WORD_BITS).max(1);
+
+    let mut phrase = Vec::new();
+    while !num.is_zero() || phrase.len() < count {
+        let index = (&num % WORDS).to_usize().unwrap();
+        phrase.push(words[index].as_str());
+        num >>= WORD_BITS;
+    }
+    phrase.reverse();
+    Ok(phrase.join(" "))
+}
+
+/// Reads the words of a phrase back into the value, printing it in the output base
+pub fn decode(opt: &Opt, phrase: &[String]) -> Result<(), BaseError> {
+    let words = load_wordlist(opt.wordlist.as_deref())?;
+    let mut num = BigInt::zero();
+    let mut count = 0;
+    for word in phrase.iter().flat_map(|words| words.split_whitespace()) {
+        let index = words
+            .iter()
+            .position(|known| *known == word.to_lowercase())
+            .ok_or(BaseError::InvalidSyntax {
+                syntax: Syntax::Mnemonic,
+            })?;
+        num = (num << WORD_BITS) + index;
+        count += 1;
+    }
+    if count == 0 {
+        return Err(BaseError::ArgError {
+            message: "Mnemonic phrase is empty",
+        });
+    }
+
+    let parsed = Parsed {
+        base: Base::Dec,
+        num: Number::Integer(Value::new(num)),
+        width: None,
+        signed: false,
+    };
+    println!("{}", format_output(opt, &parsed)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use structopt::StructOpt;
+
+    fn encoded(value: &str, width: usize) -> String {
+        let opt = Opt::from_iter(["changebase", "--width", &width.to_string()]);
+        let parsed = Parsed {
+            base: Base::Hex,
+            num: Number::Integer(Value::from(value, Base::Hex).unwrap()),
+            width: None,
+            signed: false,
+        };
+        encode(&opt, &parsed).unwrap()
+    }
+
+    #[test]
+    fn embeds_the_bip39_english_list() {
+        let words = load_wordlist(None).unwrap();
+        assert_eq!(words[0], "abandon");
+        assert_eq!(words[2047], "zoo");
+    }
+
+    #[test]
+    fn encodes_bip39_vectors() {
+        // BIP39 test vectors, as 128 bits of entropy followed by their 4 checksum bits
+        assert_eq!(
+            encoded("3", 132),
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+        );
+        assert_eq!(
+            encoded("7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f8", 132),
+            "legal winner thank year wave sausage worth useful legal winner thank yellow"
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use crate::base::Value;
+use crate::errors::BaseError;
+use crate::opts::{Base, Opt};
+use crate::{format_output, parse_number, Number, Parsed};
+use num::{bigint::BigInt, One, ToPrimitive};
+use std::convert::TryInto;
+use std::net::Ipv6Addr;
+
+/// Writes the value as a dotted-quad IPv4 address, with the octets in hex if `hex` is set
+pub fn to_ip4(value: &BigInt, hex: bool) -> Result<String, BaseError> {
+    let address = value.to_u32().ok_or(BaseError::OverflowError {
+        message: "IPv4 addresses range from 0.0.0.0 to 255.255.255.255",
+    })?;
+    let octets: Vec<String> = address
+        .to_be_bytes()
+        .iter()
+        .map(|octet| {
+            if hex {
+                format!("{:02x}", octet)
+            } else {
+                octet.to_string()
+            }
+        })
+        .collect();
+    Ok(octets.join("."))
+}
+
+/// Reads a dotted-quad IPv4 address of four decimal octets
+pub fn parse_ip4(value: &str) -> Option<BigInt> {
+    let octets = value
+        .split('.')
+        .map(|octet| match octet.chars().all(|c| c.is_ascii_digit()) {
+            true => octet.parse::<u8>().ok(),
+            false => None,
+        })
+        .collect::<Option<Vec<u8>>>()?;
+    let octets: [u8; 4] = octets.try_into().ok()?;
+    Some(BigInt::from(u32::from_be_bytes(octets)))
+}
+
+/// Writes the value as an IPv6 address in its compressed form, e.g. 2001:db8::1
+pub fn to_ip6(value: &BigInt) -> Result<String, BaseError> {
+    let address = value.to_u128().ok_or(BaseError::OverflowError {
+        message: "IPv6 addresses range from :: to ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff",
+    })?;
+    Ok(Ipv6Addr::from(address).to_string())
+}
+
+/// Reads an IPv6 address in colon-hex form, compressed or not
+pub fn parse_ip6(value: &str) -> Option<BigInt> {
+    value
+        .parse::<Ipv6Addr>()
+        .ok()
+        .map(|address| BigInt::from(u128::from(address)))
+}
+
+/// Prints the netmask of a prefix length such as /24, or the prefix length of a netmask
+pub fn run_cidr(opt: &Opt, value: &str, ip6: bool) -> Result<(), BaseError> {
+    let ip6 = ip6 || value.contains(':');
+    let width = if ip6 { 128 } else { 32 };
+
+    if let Some(length) = value.strip_prefix('/') {
+        let length = length
+            .parse::<usize>()
+            .ok()
+            .filter(|length| *length <= width)
+            .ok_or(BaseError::ArgError {
+                message: "Prefix lengths range from /0 to /32, or /128 with --ip6",
+            })?;
+        let mask = ((BigInt::one() << length) - 1) << (width - length);
+        let parsed = Parsed {
+            base: Base::Dec,
+            num: Number::Integer(Value::new(mask)),
+            width: None,
+            signed: false,
+        };
+        // the mask is padded to the address bits, but is not a two's complement value
+        let mut opt = opt.clone();
+        opt.bits = opt.bits.or(Some(width));
+        println!("{}", format_output(&opt, &parsed)?);
+        return Ok(());
+    }
+
+    let mask = if ip6 {
+        parse_ip6(value)
+    } else {
+        parse_ip4(value)
+    };
+    let mask = match mask {
+        Some(mask) => mask,
+        None => parse_number(opt, value, None)?
+            .integer()?
+            .clone()
+            .into_inner(),
+    };
+    let length = Value::new(mask.clone()).popcount() as usize;
+    if length > width || mask != ((BigInt::one() << length) - 1) << (width - length) {
+        return Err(BaseError::ArgError {
+            message: "Netmask must be a run of set bits followed by clear bits",
+        });
+    }
+    println!("/{}", length);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_ip4() {
+        for (address, value) in [
+            ("0.0.0.0", 0u32),
+            ("192.168.1.1", 0xc0a80101),
+            ("255.255.255.255", u32::MAX),
+        ] {
+            assert_eq!(parse_ip4(address), Some(BigInt::from(value)));
+            assert_eq!(to_ip4(&BigInt::from(value), false).unwrap(), address);
+        }
+        assert_eq!(
+            to_ip4(&BigInt::from(0xc0a80101u32), true).unwrap(),
+            "c0.a8.01.01"
+        );
+        assert!(to_ip4(&BigInt::from(1u64 << 32), false).is_err());
+        assert!(to_ip4(&BigInt::from(-1), false).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_ip4() {
+        for address in [
+            "256.0.0.1",
+            "1.2.3",
+            "1.2.3.4.5",
+            "1..3.4",
+            "1.2.3.-4",
+            "1.2.3.+4",
+            "a.b.c.d",
+        ] {
+            assert_eq!(parse_ip4(address), None, "{:?}", address);
+        }
+    }
+
+    #[test]
+    fn round_trips_ip6() {
+        for (address, value) in [
+            ("::", 0u128),
+            ("::1", 1),
+            ("2001:db8::1", 0x2001_0db8_0000_0000_0000_0000_0000_0001),
+            ("fe80::1:0:0:2", 0xfe80_0000_0000_0000_0001_0000_0000_0002),
+            ("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff", u128::MAX),
+        ] {
+            assert_eq!(parse_ip6(address), Some(BigInt::from(value)), "{}", address);
+            assert_eq!(to_ip6(&BigInt::from(value)).unwrap(), address);
+        }
+        let value = Some(BigInt::from(0x2001_0db8_0000_0000_0000_0000_0000_0001u128));
+        assert_eq!(parse_ip6("2001:0DB8:0000:0000:0000:0000:0000:0001"), value);
+        assert_eq!(parse_ip6("2001:db8:0::0:1"), value);
+        assert!(to_ip6(&(BigInt::from(1) << 128)).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_ip6() {
+        for address in ["", ":", "1::2::3", "1:2:3:4:5:6:7:8:9", "12345::", "g::1"] {
+            assert_eq!(parse_ip6(address), None, "{:?}", address);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::base::Value;
+use crate::errors::BaseError;
+use crate::opts::{Operator, Opt};
+use crate::{format_output, parse_number, Parsed};
+use num::{bigint::BigInt, Integer, One, Signed, ToPrimitive, Zero};
+
+/// Applies the operator to the operands, printing the result in the output base
+pub fn run(
+    opt: &Opt,
+    operator: &Operator,
+    a: &str,
+    b: Option<&str>,
+    c: Option<&str>,
+) -> Result<(), BaseError> {
+    let lhs = parse_number(opt, a, None)?;
+    let rhs = match (operator, b) {
+        (Operator::Not, None) => None,
+        (Operator::Not, Some(_)) => {
+            return Err(BaseError::ArgError {
+                message: "not takes a single value",
+            })
+        }
+        (_, Some(b)) => Some(parse_number(opt, b, None)?),
+        (_, None) => {
+            return Err(BaseError::ArgError {
+                message: "Operator requires two values",
+            })
+        }
+    };
+    let modulus = match (operator, c) {
+        (Operator::Powmod, Some(c)) => Some(parse_number(opt, c, None)?),
+        (Operator::Powmod, None) => {
+            return Err(BaseError::ArgError {
+                message: "powmod takes a base, an exponent and a modulus",
+            })
+        }
+        (_, Some(_)) => {
+            return Err(BaseError::ArgError {
+                message: "Only powmod takes three values",
+            })
+        }
+        (_, None) => None,
+    };
+
+    let width = opt
+        .width
+        .or(lhs.width)
+        .or_else(|| rhs.as_ref().and_then(|rhs| rhs.width));
+    let a = operand(&lhs, width)?;
+    let result = match &rhs {
+        None => {
+            // without a width, invert the smallest power of two bytes holding the value
+            let bits =
+                width.unwrap_or_else(|| (a.bits() as usize).div_ceil(8).next_power_of_two() * 8);
+            a ^ mask(bits)
+        }
+        Some(rhs) => {
+            let b = operand(rhs, width)?;
+            match operator {
+                Operator::And => a & b,
+                Operator::Or => a | b,
+                Operator::Xor => a ^ b,
+                Operator::Shl => a << shift(&b)?,
+                Operator::Shr => a >> shift(&b)?,
+                Operator::Powmod => {
+                    let modulus = operand(modulus.as_ref().unwrap(), width)?;
+                    powmod(&a, &b, &modulus)?
+                }
+                Operator::Not => unreachable!(),
+            }
+        }
+    };
+
+    // results wrap around the width, so shifted out bits are dropped
+    let result = match width {
+        Some(width) => result & mask(width),
+        None => result,
+    };
+    let parsed = Parsed { width, ..lhs };
+    let result = reduce(opt, Value::new(result))?;
+    println!("{}", format_output(opt, &parsed.with_value(result))?);
+    Ok(())
+}
+
+/// Returns the operand as an unsigned integer of the given width
+fn operand(parsed: &Parsed, width: Option<usize>) -> Result<BigInt, BaseError> {
+    let num = parsed.integer()?;
+    match width {
+        Some(width) => Ok(num.to_unsigned(width)?.into_inner()),
+        None => Ok(num.clone().into_inner()),
+    }
+}
+
+fn mask(width: usize) -> BigInt {
+    (BigInt::one() << width) - 1
+}
+
+fn shift(amount: &BigInt) -> Result<usize, BaseError> {
+    amount.to_usize().ok_or(BaseError::ArgError {
+        message: "Shift amount must be a non-negative integer",
+    })
+}
+
+fn powmod(base: &BigInt, exponent: &BigInt, modulus: &BigInt) -> Result<BigInt, BaseError> {
+    if exponent.is_negative() {
+        return Err(BaseError::ArgError {
+            message: "powmod needs a non-negative exponent",
+        });
+    }
+    if !modulus.is_positive() {
+        return Err(BaseError::ArgError {
+            message: "powmod needs a positive modulus",
+        });
+    }
+    Ok(base.modpow(exponent, modulus))
+}
+
+/// Reduces the value modulo --mod, if given, to between zero and the modulus
+pub fn reduce(opt: &Opt, num: Value) -> Result<Value, BaseError> {
+    let modulus = match &opt.modulus {
+        Some(modulus) => parse_number(opt, modulus, None)?.integer()?.clone(),
+        None => return Ok(num),
+    };
+    let modulus = modulus.into_inner();
+    if modulus.is_zero() {
+        return Err(BaseError::ArgError {
+            message: "--mod must not be zero",
+        });
+    }
+    Ok(Value::new(num.into_inner().mod_floor(&modulus)))
+}
@@ -1,31 +1,254 @@
-use crate::base::detect_base;
+use crate::base::{detect_base, explain_detection, DetectStrategy};
+use crate::command::Command;
 use crate::errors::BaseError;
-use clap::arg_enum;
+use crate::expr::MAX_BITS;
+use crate::float::FloatFormat;
+use clap::{arg_enum, AppSettings, ArgMatches, ErrorKind};
+use std::io::{self, IsTerminal};
+use std::process;
 use structopt::StructOpt;
 
 arg_enum! {
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, PartialEq)]
     pub enum Base {
         Bin,
         Oct,
         Dec,
         Hex,
+        Roman,
+        Balanced3,
+        Neg2,
+        Col,
+        Hms,
+        Factorial,
+        Doz,
+        Ip4,
+        Ip6,
+        Perm,
+        Uuid,
+        Size,
+        Address,
+        Hexdump,
+    }
+}
+
+arg_enum! {
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum DozenalDigits {
+        Xe,
+        Pitman,
+        Ab,
+    }
+}
+
+arg_enum! {
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum ColorWhen {
+        Auto,
+        Always,
+        Never,
+    }
+}
+
+arg_enum! {
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum TableFormat {
+        Md,
+        Csv,
+        Tsv,
+        Aligned,
+    }
+}
+
+arg_enum! {
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum TimeUnit {
+        S,
+        Ms,
+        Us,
+        Ns,
+    }
+}
+
+arg_enum! {
+    #[derive(Debug, Clone)]
+    pub enum PrefixStyle {
+        C,
+        Rust,
+        Python,
+        None,
+    }
+}
+
+arg_enum! {
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Format {
+        C,
+        Rust,
+        Python,
+        Go,
+        Java,
+        Verilog,
+    }
+}
+
+arg_enum! {
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum ByteFormat {
+        C,
+        Rust,
+        Python,
+        Escape,
+    }
+}
+
+arg_enum! {
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Endian {
+        Be,
+        Le,
+    }
+}
+
+arg_enum! {
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Operator {
+        And,
+        Or,
+        Xor,
+        Not,
+        Shl,
+        Shr,
+        Powmod,
+    }
+}
+
+impl Format {
+    /// Returns the prefix convention of a programming language format
+    pub fn prefix_style(&self) -> Option<PrefixStyle> {
+        match *self {
+            Format::C | Format::Java => Some(PrefixStyle::C),
+            Format::Rust | Format::Python | Format::Go => Some(PrefixStyle::Rust),
+            Format::Verilog => None,
+        }
+    }
+
+    /// Returns true if the language allows `_` digit separators in integer literals
+    pub fn allows_separators(&self) -> bool {
+        match *self {
+            Format::Rust | Format::Python | Format::Go | Format::Java => true,
+            Format::C | Format::Verilog => false,
+        }
     }
 }
 
 impl Base {
+    /// Returns the positional bases shown when no output base is given
+    pub fn all() -> Vec<Base> {
+        vec![Base::Bin, Base::Oct, Base::Dec, Base::Hex]
+    }
+
+    /// Short lowercase name used as a label, e.g. in the all-bases view
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Base::Bin => "bin",
+            Base::Oct => "oct",
+            Base::Dec => "dec",
+            Base::Hex => "hex",
+            Base::Roman => "roman",
+            Base::Balanced3 => "balanced3",
+            Base::Neg2 => "neg2",
+            Base::Col => "col",
+            Base::Hms => "hms",
+            Base::Factorial => "factorial",
+            Base::Doz => "doz",
+            Base::Ip4 => "ip4",
+            Base::Ip6 => "ip6",
+            Base::Perm => "perm",
+            Base::Uuid => "uuid",
+            Base::Size => "size",
+            Base::Address => "address",
+            Base::Hexdump => "hexdump",
+        }
+    }
+
+    /// Returns the literal prefix for this base in the given language convention
+    pub fn prefix(&self, style: &PrefixStyle) -> &'static str {
+        match (style, self) {
+            (PrefixStyle::None, _) | (_, Base::Dec) => "",
+            (_, Base::Roman) | (_, Base::Balanced3) | (_, Base::Neg2) => "",
+            (_, Base::Col) | (_, Base::Hms) | (_, Base::Factorial) | (_, Base::Doz) => "",
+            (_, Base::Ip4)
+            | (_, Base::Ip6)
+            | (_, Base::Perm)
+            | (_, Base::Uuid)
+            | (_, Base::Size)
+            | (_, Base::Address)
+            | (_, Base::Hexdump) => "",
+            (_, Base::Bin) => "0b",
+            (_, Base::Hex) => "0x",
+            (PrefixStyle::C, Base::Oct) => "0",
+            (_, Base::Oct) => "0o",
+        }
+    }
+
     pub fn repr(&self) -> String {
         match *self {
             Base::Bin => "Binary".to_string(),
             Base::Oct => "Octal".to_string(),
             Base::Dec => "Decimal".to_string(),
             Base::Hex => "Hexadecimal".to_string(),
+            Base::Roman => "Roman numerals".to_string(),
+            Base::Balanced3 => "Balanced ternary".to_string(),
+            Base::Neg2 => "Negabinary".to_string(),
+            Base::Col => "Spreadsheet column".to_string(),
+            Base::Hms => "Hours, minutes and seconds".to_string(),
+            Base::Factorial => "Factorial".to_string(),
+            Base::Doz => "Dozenal".to_string(),
+            Base::Ip4 => "IPv4 address".to_string(),
+            Base::Ip6 => "IPv6 address".to_string(),
+            Base::Perm => "Permissions".to_string(),
+            Base::Uuid => "UUID".to_string(),
+            Base::Size => "Byte size".to_string(),
+            Base::Address => "Ethereum address".to_string(),
+            Base::Hexdump => "Hexdump".to_string(),
+        }
+    }
+
+    /// Describes how values of this base are written, shown when one is not
+    pub fn format_hint(&self) -> &'static str {
+        match *self {
+            Base::Bin => "Binary: only enter the digits 0 and 1",
+            Base::Oct => "Octal: only enter the digits 0-7",
+            Base::Dec => "Decimal: only enter the digits 0-9",
+            Base::Hex => "Hexadecimal: only enter the digits 0-9 and a-f",
+            Base::Roman => "Roman: only enter numerals such as MCMXCIV",
+            Base::Balanced3 => "Balanced ternary: only enter the digits T, 0 and 1",
+            Base::Neg2 => "Negabinary: only enter the digits 0 and 1",
+            Base::Col => "Column: only enter the letters A-Z",
+            Base::Hms => "Time: only enter H:MM:SS, MM:SS or seconds",
+            Base::Factorial => {
+                "Factorial: each digit must be at most its position, counting from 0 at the right"
+            }
+            Base::Doz => "Dozenal: only enter the digits 0-9, X and E",
+            Base::Ip4 => "IPv4: only enter four decimal octets such as 192.168.1.1",
+            Base::Ip6 => "IPv6: only enter colon-separated hex groups such as 2001:db8::1",
+            Base::Perm => "Permissions: only enter symbolic modes such as rwxr-xr-x",
+            Base::Uuid => "UUID: only enter 32 hex digits, optionally grouped 8-4-4-4-12",
+            Base::Size => "Size: only enter a whole number of bytes such as 4K, 2MiB or 1.5GB",
+            Base::Address => "Address: only enter 0x followed by 40 hex digits",
+            Base::Hexdump => "Hexdump: only enter lines of xxd or hexdump -C output",
         }
     }
 }
 
 #[derive(Clone, Debug, StructOpt)]
-#[structopt(name = "base", about = "numeric base converter")]
+#[structopt(
+    name = "base",
+    about = "numeric base converter",
+    after_help = "EXIT CODES:\n    0    Success\n    1    Invalid arguments, an I/O error, differing numbers or a false check\n    2    A value could not be parsed or evaluated\n    3    The base of a value could not be detected\n    4    A value is out of range for its base, width or type\n    5    Some values of a batch failed with --keep-going",
+    global_setting = AppSettings::AllowNegativeNumbers
+)]
 pub struct Opt {
     /// Input base to use. If not given, attempts to detect
     #[structopt(
@@ -33,99 +256,681 @@ pub struct Opt {
         short = "in",
         possible_values = &Base::variants(),
         case_insensitive = true,
+        global = true,
     )]
     pub input: Option<Base>,
 
-    /// Output base to use
+    /// Output base to use. If not given, shows all bases
     #[structopt(
         long = "output",
         short = "out",
         possible_values = &Base::variants(),
         case_insensitive = true,
+        global = true,
     )]
     pub output: Option<Base>,
 
-    pub value: String,
+    #[structopt(subcommand)]
+    pub cmd: Option<Command>,
+
+    /// only detect bases from an explicit 0x, 0b or 0o prefix, or an h, b, o, q or d suffix
+    #[structopt(
+        long = "detect-strict",
+        conflicts_with_all = &["detect-prefer", "detect-legacy"],
+        global = true,
+    )]
+    pub detect_strict: bool,
+
+    /// prefer this base when detecting the base of an unprefixed value
+    #[structopt(
+        long = "detect-prefer",
+        possible_values = &Base::variants(),
+        case_insensitive = true,
+        conflicts_with = "detect-legacy",
+        global = true,
+    )]
+    pub detect_prefer: Option<Base>,
+
+    /// detect bases by trying binary, octal, decimal, then hex
+    #[structopt(long = "detect-legacy", global = true)]
+    pub detect_legacy: bool,
+
+    /// read values with a leading zero, e.g. 0755, as C-style octal
+    #[structopt(long = "c-octal", global = true)]
+    pub c_octal: bool,
+
+    /// Bases to show when no output base is given, in order, e.g. --show bin,hex
+    #[structopt(
+        long = "show",
+        possible_values = &Base::variants(),
+        case_insensitive = true,
+        use_delimiter = true,
+        number_of_values = 1,
+        global = true,
+    )]
+    pub show: Vec<Base>,
+
+    /// Values to convert. Use `-`, or pipe values in, to convert whitespace-separated values from stdin
+    pub values: Vec<String>,
+
+    /// Convert only the numbers in the input that match this regular expression, or its first group, one per line
+    #[structopt(long = "extract", global = true)]
+    pub extract: Option<String>,
+
+    /// Read values to convert from a file, one per line, or from stdin for `-`
+    #[structopt(short = "f", long = "file", conflicts_with = "values")]
+    pub file: Option<String>,
 
     #[structopt(flatten)]
     short_base_opts: ShortBaseOpts,
 
-    /// add verbosity
-    #[structopt(short)]
-    pub verbose: bool,
+    #[structopt(flatten)]
+    pub float_opts: FloatOpts,
+
+    /// Bit width for two's complement values, e.g. 8, 16, 32, 64, up to 2^24, or bytes per line of hexdump output
+    #[structopt(long = "width", global = true)]
+    pub width: Option<usize>,
+
+    /// Interpret the value as a signed two's complement number of the given width
+    #[structopt(long = "signed", requires = "width", global = true)]
+    pub signed: bool,
+
+    /// Alternate the color of each nibble of binary and byte of hex output, or of each --group. Auto colors output to a terminal
+    #[structopt(
+        long = "color",
+        possible_values = &ColorWhen::variants(),
+        case_insensitive = true,
+        default_value = "auto",
+        global = true,
+    )]
+    pub color: ColorWhen,
+
+    /// Write hex digits in uppercase
+    #[structopt(long = "upper", global = true)]
+    pub upper: bool,
+
+    /// Left-pad the output with zeros to at least this many digits
+    #[structopt(long = "pad", global = true)]
+    pub pad: Option<usize>,
+
+    /// Left-pad hex output with zeros to a whole number of 32 byte words, as in ABI encoding
+    #[structopt(long = "pad-word", global = true)]
+    pub pad_word: bool,
+
+    /// Split hex output into lines of this many bytes, each after its byte offset
+    #[structopt(long = "chunk", global = true)]
+    pub chunk: Option<usize>,
+
+    /// Left-pad binary, octal and hex output with zeros to show this many bits
+    #[structopt(long = "bits", global = true)]
+    pub bits: Option<usize>,
+
+    /// Separate output digits into groups, e.g. --group=8. Defaults to 4 for binary and hex, 3 for octal and decimal
+    #[structopt(long = "group", require_equals = true, global = true)]
+    pub group: Option<Option<usize>>,
+
+    /// Separator placed between digit groups
+    #[structopt(long = "separator", default_value = "_", global = true)]
+    pub separator: String,
+
+    /// Prefix the output with its base, e.g. 0x, 0b or 0o
+    #[structopt(long = "prefix", global = true)]
+    pub prefix: bool,
+
+    /// Prefix convention to use, implies --prefix
+    #[structopt(
+        long = "prefix-style",
+        possible_values = &PrefixStyle::variants(),
+        case_insensitive = true,
+        global = true,
+    )]
+    pub prefix_style: Option<PrefixStyle>,
+
+    /// Render the output as a literal in the given language, e.g. 0xff_ff for rust or 8'hff for verilog
+    #[structopt(
+        long = "format",
+        possible_values = &Format::variants(),
+        case_insensitive = true,
+        global = true,
+    )]
+    pub format: Option<Format>,
+
+    /// Type suffix appended to language literals, e.g. u64 or ULL
+    #[structopt(long = "type-suffix", requires = "format", global = true)]
+    pub type_suffix: Option<String>,
+
+    /// Print the value's bytes as an array or string literal instead of digits
+    #[structopt(
+        long = "bytes",
+        possible_values = &ByteFormat::variants(),
+        case_insensitive = true,
+        conflicts_with = "json",
+        global = true,
+    )]
+    pub bytes: Option<ByteFormat>,
+
+    /// Round the value up to the next power of two
+    #[structopt(long = "next-pow2", global = true)]
+    pub next_pow2: bool,
+
+    /// Also print the UTC datetime of the value as a Unix timestamp in seconds, or in the given unit, e.g. --as-timestamp=ms
+    #[structopt(
+        long = "as-timestamp",
+        require_equals = true,
+        possible_values = &TimeUnit::variants(),
+        case_insensitive = true,
+        global = true,
+    )]
+    pub as_timestamp: Option<Option<TimeUnit>>,
+
+    /// Reverse the byte order of the value, e.g. --swap-bytes=32. Defaults to --width,
+    /// or the smallest power of two bytes that holds the value
+    #[structopt(long = "swap-bytes", require_equals = true, global = true)]
+    pub swap_bytes: Option<Option<usize>>,
+
+    /// Byte order used by --bytes
+    #[structopt(
+        long = "endian",
+        possible_values = &Endian::variants(),
+        case_insensitive = true,
+        default_value = "be",
+        global = true,
+    )]
+    pub endian: Endian,
+
+    /// Write the octets of IPv4 addresses in hex, e.g. c0.a8.01.01
+    #[structopt(long = "ip-hex", global = true)]
+    pub ip_hex: bool,
+
+    /// Use powers of 1000 rather than 1024 for sizes such as 4K, except units with an i such as KiB
+    #[structopt(long = "si", global = true)]
+    pub si: bool,
+
+    /// Write Roman numerals above 3999 with overlined thousands, e.g. V̅ for 5000
+    #[structopt(long = "vinculum", global = true)]
+    pub vinculum: bool,
+
+    /// Digits used for ten and eleven in dozenal output: X and E, the Pitman digits ↊ and ↋, or a and b
+    #[structopt(
+        long = "doz-digits",
+        possible_values = &DozenalDigits::variants(),
+        case_insensitive = true,
+        default_value = "xe",
+        global = true,
+    )]
+    pub doz_digits: DozenalDigits,
+
+    /// Maximum number of digits to print after the radix point of fractional values
+    #[structopt(long = "precision", default_value = "10", global = true)]
+    pub precision: usize,
+
+    /// Print only the number of set bits of the value
+    #[structopt(
+        long = "popcount",
+        conflicts_with_all = &["msb", "ctz", "bytes", "json"],
+        global = true,
+    )]
+    pub popcount: bool,
+
+    /// Print only the index of the highest set bit of the value
+    #[structopt(long = "msb", conflicts_with_all = &["ctz", "bytes", "json"], global = true)]
+    pub msb: bool,
+
+    /// Print only the number of trailing zero bits of the value
+    #[structopt(long = "ctz", conflicts_with_all = &["bytes", "json"], global = true)]
+    pub ctz: bool,
+
+    /// Print only the sum of the digits of the value in the base it was read in
+    #[structopt(
+        long = "digit-sum",
+        conflicts_with_all = &["digital-root", "popcount", "msb", "ctz", "bytes", "json"],
+        global = true,
+    )]
+    pub digit_sum: bool,
+
+    /// Print only the digit sum of the value repeated down to a single digit, in the base it was read in
+    #[structopt(
+        long = "digital-root",
+        conflicts_with_all = &["popcount", "msb", "ctz", "bytes", "json"],
+        global = true,
+    )]
+    pub digital_root: bool,
+
+    /// Print yes if the value is a multiple of this value, otherwise no
+    #[structopt(
+        long = "divisible-by",
+        conflicts_with_all = &["digit-sum", "digital-root", "popcount", "msb", "ctz", "bytes", "json"],
+        global = true,
+    )]
+    pub divisible_by: Option<String>,
+
+    /// Write the value in a mixed radix, most significant first, e.g. --mixed-radix 24,60,60 for days:hours:minutes:seconds
+    #[structopt(
+        long = "mixed-radix",
+        use_delimiter = true,
+        number_of_values = 1,
+        global = true
+    )]
+    pub mixed_radix: Vec<u32>,
+
+    /// Write the value as a phrase of BIP39 English words, eleven bits per word
+    #[structopt(long = "mnemonic", conflicts_with_all = &["bytes", "json"], global = true)]
+    pub mnemonic: bool,
+
+    /// Read mnemonic words from this file of 2048 words, one per line, instead of the BIP39 English list
+    #[structopt(long = "wordlist", global = true)]
+    pub wordlist: Option<String>,
+
+    /// Draw the bits of the value under a ruler of bit indices, up to --width bits
+    #[structopt(
+        long = "diagram",
+        conflicts_with_all = &["popcount", "msb", "ctz", "bytes", "json"],
+        global = true,
+    )]
+    pub diagram: bool,
+
+    /// Show each step of the conversion: the positional expansion of the digits read, then the repeated division by the output base. With detect, explain why the base was chosen
+    #[structopt(
+        short = "e",
+        long = "explain",
+        conflicts_with_all = &["popcount", "msb", "ctz", "diagram", "bytes", "json"],
+        global = true,
+    )]
+    pub explain: bool,
+
+    /// Show each digit of the value with its positional weight and contribution, then their total
+    #[structopt(
+        long = "places",
+        conflicts_with_all = &["popcount", "msb", "ctz", "diagram", "explain", "bytes", "json"],
+        global = true,
+    )]
+    pub places: bool,
+
+    /// Show the value wrapped to u8/i8 through u64/i64, marking types that cannot hold it
+    #[structopt(
+        long = "widths",
+        conflicts_with_all = &["popcount", "msb", "ctz", "diagram", "bytes", "json"],
+        global = true,
+    )]
+    pub widths: bool,
+
+    /// Fail unless the value fits in the integer type, e.g. u8, i32 or u128
+    #[structopt(long = "fits", global = true)]
+    pub fits: Option<String>,
+
+    /// Reduce the result modulo this value before printing it, e.g. --mod 0x100
+    #[structopt(long = "mod", global = true)]
+    pub modulus: Option<String>,
+
+    /// Decode the value into named bit fields read from a TOML or JSON register definition, e.g. regs.toml#STATUS
+    #[structopt(
+        long = "fields",
+        conflicts_with_all = &["popcount", "msb", "ctz", "diagram", "widths", "bytes", "json"],
+        global = true,
+    )]
+    pub fields: Option<String>,
+
+    /// List the named flags set in the value, given as NAME=MASK,... or a definition file, e.g. READ=0x1,WRITE=0x2
+    #[structopt(
+        long = "flags",
+        conflicts_with_all = &["popcount", "msb", "ctz", "diagram", "widths", "fields", "bytes", "json"],
+        global = true,
+    )]
+    pub flags: Option<String>,
+
+    /// Print the converted values as rows of a Markdown, CSV or TSV table under a header of bases, or as columns aligned on the radix point
+    #[structopt(
+        long = "table",
+        possible_values = &TableFormat::variants(),
+        case_insensitive = true,
+        conflicts_with_all = &["json", "f32", "f64", "as-float"],
+        global = true,
+    )]
+    pub table: Option<TableFormat>,
+
+    /// print one JSON object per converted value
+    #[structopt(long = "json", conflicts_with_all = &["f32", "f64", "as-float"], global = true)]
+    pub json: bool,
+
+    /// add verbosity: -v shows the base each value is read in, -vv also why each base was detected and how values are converted, -vvv also timings
+    #[structopt(short, parse(from_occurrences), global = true)]
+    pub verbose: u8,
+
+    /// Read NUL-terminated values from stdin, as written by find -print0, instead of whitespace-separated ones
+    #[structopt(long = "stdin0", global = true)]
+    pub stdin0: bool,
+
+    /// Terminate each result with a NUL rather than a newline, for xargs -0
+    #[structopt(long = "print0", global = true)]
+    pub print0: bool,
+
+    /// Report the time taken to parse and convert each value, and the whole batch, on stderr
+    #[structopt(long = "time", global = true)]
+    pub time: bool,
+
+    /// Report values that fail to convert and carry on with the rest, rather than stopping at the first
+    #[structopt(long = "keep-going", global = true)]
+    pub keep_going: bool,
+
+    /// Leave out the newline after the last result, as with echo -n
+    #[structopt(short = "n", long = "no-newline", global = true)]
+    pub no_newline: bool,
+
+    /// suppress informational messages, which are otherwise printed to stderr
+    #[structopt(short, long, conflicts_with = "verbose", global = true)]
+    pub quiet: bool,
+
+    /// The parsed command line, so options given on it can be told from their defaults
+    #[structopt(skip)]
+    matches: Option<ArgMatches<'static>>,
 }
 
 #[derive(Clone, Debug, StructOpt)]
 struct ShortBaseOpts {
     /// use binary as input base
-    #[structopt(long = "ib")]
+    #[structopt(long = "ib", global = true)]
     pub binary_input: bool,
 
     /// use octal as input base
-    #[structopt(long = "io")]
+    #[structopt(long = "io", global = true)]
     pub octal_input: bool,
 
     /// use decimal as input base
-    #[structopt(long = "id")]
+    #[structopt(long = "id", global = true)]
     pub decimal_input: bool,
 
     /// use hex as input base
-    #[structopt(long = "ih")]
+    #[structopt(long = "ih", global = true)]
     pub hex_input: bool,
 
     /// use binary as output base
-    #[structopt(long = "ob")]
+    #[structopt(long = "ob", global = true)]
     pub binary_output: bool,
 
     /// use octal as output base
-    #[structopt(long = "oo")]
+    #[structopt(long = "oo", global = true)]
     pub octal_output: bool,
 
     /// use decimal as output base
-    #[structopt(long = "od")]
+    #[structopt(long = "od", global = true)]
     pub decimal_output: bool,
 
     /// use hex as output base
-    #[structopt(long = "oh")]
+    #[structopt(long = "oh", global = true)]
     pub hex_output: bool,
 }
 
+#[derive(Clone, Debug, StructOpt)]
+pub struct FloatOpts {
+    /// inspect the value as an IEEE 754 single precision float
+    #[structopt(long = "f32", conflicts_with = "f64", global = true)]
+    pub f32: bool,
+
+    /// inspect the value as an IEEE 754 double precision float
+    #[structopt(long = "f64", global = true)]
+    pub f64: bool,
+
+    /// decode the value as a float bit pattern, given in hex unless an input base is specified, as an f32 if it is at most 32 bits wide
+    #[structopt(long = "as-float", global = true)]
+    pub as_float: bool,
+}
+
 impl Opt {
-    pub fn get_input(&self) -> Result<Base, BaseError> {
+    /// Parses the command line arguments, suggesting the closest flag or value when an
+    /// argument is not recognised and clap has no suggestion of its own
+    pub fn from_args_with_suggestions() -> Opt {
+        let error = match Opt::clap().get_matches_from_safe(std::env::args_os()) {
+            Ok(matches) => {
+                let mut opt = Opt::from_clap(&matches);
+                opt.matches = Some(matches);
+                return opt;
+            }
+            Err(error) => error,
+        };
+        let (first, rest) = error
+            .message
+            .split_once('\n')
+            .unwrap_or((&error.message, ""));
+        let candidates = match (&error.kind, &error.info) {
+            (ErrorKind::UnknownArgument, Some(info)) => {
+                let flags = known_flags();
+                // the options of changebase that are not global, such as --file, are only
+                // read before the subcommand
+                if let Some(arg) = info.first().filter(|arg| flags.contains(arg)) {
+                    eprintln!(
+                        "{}\n\t'{}' is an option of changebase itself, so it goes before the subcommand\n{}",
+                        first, arg, rest
+                    );
+                    process::exit(1);
+                }
+                info.first().map(|arg| (arg, flags))
+            }
+            (ErrorKind::InvalidValue, Some(info)) => info
+                .get(1)
+                .map(|value| (value, possible_values(&error.message))),
+            _ => None,
+        };
+        let suggestion = candidates
+            .filter(|_| !error.message.contains("Did you mean"))
+            .and_then(|(arg, candidates)| closest(arg, &candidates));
+        match suggestion {
+            Some(suggestion) => {
+                eprintln!("{}\n\tDid you mean '{}'?\n{}", first, suggestion, rest);
+                process::exit(1);
+            }
+            None => error.exit(),
+        }
+    }
+
+    /// Returns true if any of the named arguments was given on the command line, before
+    /// or after the subcommand
+    pub fn given(&self, names: &[&str]) -> bool {
+        self.matches
+            .as_ref()
+            .is_some_and(|matches| names.iter().any(|name| matches.occurrences_of(name) > 0))
+    }
+
+    /// Fails for widths and padding too large to build, so a typo cannot exhaust memory
+    pub fn check_widths(&self) -> Result<(), BaseError> {
+        let too_large = |len: Option<usize>| len.is_some_and(|len| len as u64 > MAX_BITS);
+        if too_large(self.width) {
+            return Err(BaseError::ArgError {
+                message: "Width: must be at most 2^24 bits",
+            });
+        }
+        if too_large(self.pad) {
+            return Err(BaseError::ArgError {
+                message: "Pad: must be at most 2^24 digits",
+            });
+        }
+        if too_large(self.bits) {
+            return Err(BaseError::ArgError {
+                message: "Bits: must be at most 2^24",
+            });
+        }
+        Ok(())
+    }
+
+    pub fn explicit_input(&self) -> Option<Base> {
         if self.input.is_some() {
-            Ok(self.input.clone().unwrap())
+            self.input.clone()
         } else if self.short_base_opts.binary_input {
-            Ok(Base::Bin)
+            Some(Base::Bin)
         } else if self.short_base_opts.octal_input {
-            Ok(Base::Oct)
+            Some(Base::Oct)
         } else if self.short_base_opts.decimal_input {
-            Ok(Base::Dec)
+            Some(Base::Dec)
         } else if self.short_base_opts.hex_input {
-            Ok(Base::Hex)
+            Some(Base::Hex)
         } else {
-            detect_base(self.value.clone())
-                .map_err(|_| BaseError::ArgError {
-                    message: "No input base specified",
-                })
-                .inspect(|b| println!("Detected base {}", b.repr()))
+            None
         }
     }
 
-    pub fn get_output(&self) -> Result<Base, BaseError> {
+    pub fn get_input(&self, value: &str) -> Result<Base, BaseError> {
+        if let Some(base) = self.explicit_input() {
+            Ok(base)
+        } else {
+            detect_base(value, self.detect_strategy()).inspect(|b| {
+                if !self.quiet {
+                    eprintln!("Detected base {}", b.repr())
+                }
+                self.trace(2, || explain_detection(value, b.clone()));
+            })
+        }
+    }
+
+    /// Returns the requested output base, or None to show all bases
+    pub fn get_output(&self) -> Option<Base> {
         if self.output.is_some() {
-            Ok(self.output.clone().unwrap())
+            self.output.clone()
         } else if self.short_base_opts.binary_output {
-            Ok(Base::Bin)
+            Some(Base::Bin)
         } else if self.short_base_opts.octal_output {
-            Ok(Base::Oct)
+            Some(Base::Oct)
         } else if self.short_base_opts.decimal_output {
-            Ok(Base::Dec)
+            Some(Base::Dec)
         } else if self.short_base_opts.hex_output {
-            Ok(Base::Hex)
+            Some(Base::Hex)
         } else {
-            Err(BaseError::ArgError {
-                message: "No output base specified",
-            })
+            None
+        }
+    }
+
+    /// Writes a diagnostic to stderr if the verbosity is at least `level`, so that stdout
+    /// only ever holds results
+    pub fn trace(&self, level: u8, message: impl FnOnce() -> String) {
+        if self.verbose >= level && !self.quiet {
+            eprintln!("{}", message());
+        }
+    }
+
+    /// Returns the float format to inspect the value in, if --f32, --f64 or --as-float is
+    /// given. Without --f32 or --f64, --as-float picks the format by the pattern's width.
+    pub fn float_format(&self, value: &str) -> Option<FloatFormat> {
+        if self.float_opts.f32 {
+            Some(FloatFormat::F32)
+        } else if self.float_opts.f64 {
+            Some(FloatFormat::F64)
+        } else if self.float_opts.as_float {
+            let input = self.explicit_input().unwrap_or(Base::Hex);
+            Some(FloatFormat::of_pattern(value, input))
+        } else {
+            None
+        }
+    }
+
+    pub fn detect_strategy(&self) -> DetectStrategy {
+        if self.detect_strict {
+            DetectStrategy::Strict
+        } else if let Some(base) = &self.detect_prefer {
+            DetectStrategy::Prefer(base.clone())
+        } else if self.detect_legacy {
+            DetectStrategy::Legacy
+        } else {
+            DetectStrategy::Default
+        }
+    }
+
+    /// Returns whether to color digit groups, following NO_COLOR when writing to a terminal
+    pub fn color_groups(&self) -> bool {
+        match self.color {
+            ColorWhen::Always => true,
+            ColorWhen::Never => false,
+            ColorWhen::Auto => {
+                !self.json && std::env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal()
+            }
         }
     }
+
+    /// Returns the bases to print, the output base or the bases of the all-bases view
+    pub fn output_bases(&self) -> Vec<Base> {
+        match self.get_output() {
+            Some(output) => vec![output],
+            None => self.shown_bases(),
+        }
+    }
+
+    /// Returns the bases to list in the all-bases view
+    pub fn shown_bases(&self) -> Vec<Base> {
+        if self.show.is_empty() {
+            Base::all()
+        } else {
+            self.show.clone()
+        }
+    }
+
+    pub fn get_prefix_style(&self) -> Option<PrefixStyle> {
+        if self.prefix_style.is_some() {
+            self.prefix_style.clone()
+        } else if self.prefix {
+            Some(PrefixStyle::Rust)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the flags listed in the help text, such as --ih and -q
+fn known_flags() -> Vec<String> {
+    let mut help = Vec::new();
+    if Opt::clap().write_long_help(&mut help).is_err() {
+        return Vec::new();
+    }
+    String::from_utf8_lossy(&help)
+        .split_whitespace()
+        .map(|word| {
+            word.trim_end_matches(',')
+                .split(['=', '<'])
+                .next()
+                .unwrap_or("")
+        })
+        .filter(|word| {
+            word.starts_with('-')
+                && word.len() > 1
+                && word
+                    .trim_start_matches('-')
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// Returns the values clap lists in an invalid value error, without color codes
+fn possible_values(message: &str) -> Vec<String> {
+    let message = strip_ansi(message);
+    message
+        .split_once("[possible values: ")
+        .and_then(|(_, values)| values.split_once(']'))
+        .map(|(values, _)| values.split(", ").map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn strip_ansi(text: &str) -> String {
+    let mut result = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Returns the candidate with the smallest edit distance to the argument, ignoring case,
+/// if it is close enough to be a likely typo
+fn closest(arg: &str, candidates: &[String]) -> Option<String> {
+    let arg = arg.to_lowercase();
+    candidates
+        .iter()
+        .map(|candidate| {
+            let distance = strsim::damerau_levenshtein(&arg, &candidate.to_lowercase());
+            (distance, candidate)
+        })
+        .filter(|(distance, _)| *distance <= 2 && *distance < arg.trim_start_matches('-').len())
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.clone())
 }
@@ -0,0 +1,111 @@
+use crate::errors::BaseError;
+use num::{bigint::BigInt, ToPrimitive};
+
+/// Special bit and the letter shown in place of x for the user, group and other classes
+const SPECIAL: [(u32, char); 3] = [(0o4000, 's'), (0o2000, 's'), (0o1000, 't')];
+
+/// Writes a mode as symbolic permissions, e.g. 4755 as rwsr-xr-x. Setuid, setgid and
+/// sticky bits are shown as s or t, or S or T if the execute bit is clear.
+pub fn to_perm(value: &BigInt) -> Result<String, BaseError> {
+    let mode = value
+        .to_u32()
+        .filter(|mode| *mode <= 0o7777)
+        .ok_or(BaseError::OverflowError {
+            message: "Permission modes range from 0 to 7777 octal",
+        })?;
+
+    let mut result = String::new();
+    for (class, (special, letter)) in SPECIAL.iter().enumerate() {
+        let bits = mode >> (6 - class * 3) & 0o7;
+        result.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        result.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        result.push(match (mode & special != 0, bits & 0o1 != 0) {
+            (true, true) => *letter,
+            (true, false) => letter.to_ascii_uppercase(),
+            (false, true) => 'x',
+            (false, false) => '-',
+        });
+    }
+    Ok(result)
+}
+
+/// Reads symbolic permissions such as rwxr-xr-x, optionally led by a file type letter
+/// as in `ls -l` output, e.g. drwxr-xr-x
+pub fn parse_perm(value: &str) -> Option<BigInt> {
+    let chars: Vec<char> = value.chars().collect();
+    let chars = match chars.len() {
+        9 => &chars[..],
+        10 => &chars[1..],
+        _ => return None,
+    };
+
+    let mut mode = 0;
+    for (class, (special, letter)) in SPECIAL.iter().enumerate() {
+        let shift = 6 - class * 3;
+        let triple = &chars[class * 3..class * 3 + 3];
+        match triple[0] {
+            'r' => mode |= 0o4 << shift,
+            '-' => {}
+            _ => return None,
+        }
+        match triple[1] {
+            'w' => mode |= 0o2 << shift,
+            '-' => {}
+            _ => return None,
+        }
+        match triple[2] {
+            'x' => mode |= 0o1 << shift,
+            '-' => {}
+            c if c == *letter => mode |= special | 0o1 << shift,
+            c if c == letter.to_ascii_uppercase() => mode |= special,
+            _ => return None,
+        }
+    }
+    Some(BigInt::from(mode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        for (perm, mode) in [
+            ("---------", 0),
+            ("rwxr-xr-x", 0o755),
+            ("rw-r--r--", 0o644),
+            ("rwsr-xr-x", 0o4755),
+            ("rwxr-Sr--", 0o2744),
+            ("rwxrwxrwt", 0o1777),
+            ("rwxrwxrwT", 0o1776),
+            ("rwsrwsrwt", 0o7777),
+        ] {
+            assert_eq!(parse_perm(perm), Some(BigInt::from(mode)), "{}", perm);
+            assert_eq!(to_perm(&BigInt::from(mode)).unwrap(), perm);
+        }
+        for mode in 0..=0o7777 {
+            let perm = to_perm(&BigInt::from(mode)).unwrap();
+            assert_eq!(parse_perm(&perm), Some(BigInt::from(mode)), "{:o}", mode);
+        }
+        assert_eq!(parse_perm("drwxr-xr-x"), Some(BigInt::from(0o755)));
+        assert!(to_perm(&BigInt::from(0o10000)).is_err());
+        assert!(to_perm(&BigInt::from(-1)).is_err());
+    }
+
+    #[test]
+    fn rejects_non_permissions() {
+        for perm in [
+            "",
+            "rwxr-xr-",
+            "rwxr-xr-xx-",
+            "wrxr-xr-x",
+            "rwxr-xr-s",
+            "rwtr-xr-x",
+            "rwxrwxrws",
+            "rwxr-xr-z",
+            "rwx r-x r",
+        ] {
+            assert_eq!(parse_perm(perm), None, "{:?}", perm);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use crate::base::Value;
+use crate::errors::BaseError;
+use crate::opts::Opt;
+use crate::{format_integer, parse_number};
+use num::bigint::{BigInt, BigUint};
+use num::{Integer, One, Signed, ToPrimitive, Zero};
+
+/// Primes tried by trial division before Pollard's rho
+const TRIAL_LIMIT: u32 = 10_000;
+
+/// Miller-Rabin bases that make the test exact below 3.3 * 10^24, and a strong probable
+/// prime test above it
+const WITNESSES: &[u32] = &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// Prints the value as written, then its prime factors in ascending order in the output
+/// base, or otherwise the base of the value, as factor(1) does
+pub fn factor(opt: &Opt, value: &str) -> Result<(), BaseError> {
+    let parsed = parse_number(opt, value, None).map_err(|e| e.in_input(value))?;
+    let num = parsed.integer()?.clone().into_inner();
+    let num = match num.to_biguint().filter(|num| !num.is_zero()) {
+        Some(num) => num,
+        None => {
+            return Err(BaseError::ArgError {
+                message: "Only positive values can be factored",
+            })
+        }
+    };
+
+    let mut factors = Vec::new();
+    factorize(num, &mut factors);
+    factors.sort();
+
+    let output = opt.get_output().unwrap_or(parsed.base);
+    let mut line = format!("{}:", value);
+    for factor in factors {
+        let factor = format_integer(opt, Value::new(BigInt::from(factor)), output.clone())?;
+        line.push(' ');
+        line.push_str(&factor);
+    }
+    println!("{}", line);
+    Ok(())
+}
+
+/// Prints whether the value is prime, by Miller-Rabin
+pub fn isprime(opt: &Opt, value: &str) -> Result<(), BaseError> {
+    let parsed = parse_number(opt, value, None).map_err(|e| e.in_input(value))?;
+    let num = parsed.integer()?.clone().into_inner();
+    let prime = !num.is_negative() && is_prime(&num.magnitude().clone());
+    println!("{}", if prime { "yes" } else { "no" });
+    Ok(())
+}
+
+/// Returns true if the number is prime, certainly below 3.3 * 10^24 and with
+/// overwhelming likelihood above it
+fn is_prime(num: &BigUint) -> bool {
+    if let Some(small) = num.to_u32().filter(|small| *small <= TRIAL_LIMIT) {
+        return small >= 2 && (2..).take_while(|d| d * d <= small).all(|d| small % d != 0);
+    }
+    if WITNESSES.iter().any(|p| (num % *p).is_zero()) {
+        return false;
+    }
+
+    // num - 1 = d * 2^s with d odd
+    let minus_one = num - 1u32;
+    let s = minus_one.trailing_zeros().unwrap_or(0);
+    let d = &minus_one >> s;
+    WITNESSES.iter().all(|a| {
+        let mut x = BigUint::from(*a).modpow(&d, num);
+        if x.is_one() || x == minus_one {
+            return true;
+        }
+        for _ in 1..s {
+            x = &x * &x % num;
+            if x == minus_one {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// Pushes the prime factors of the number, dividing out small primes first and
+/// splitting what is left with Pollard's rho
+fn factorize(mut num: BigUint, factors: &mut Vec<BigUint>) {
+    for d in 2..TRIAL_LIMIT {
+        if BigUint::from(d * d) > num {
+            break;
+        }
+        while (&num % d).is_zero() {
+            factors.push(BigUint::from(d));
+            num /= d;
+        }
+    }
+    split(num, factors);
+}
+
+fn split(num: BigUint, factors: &mut Vec<BigUint>) {
+    if num.is_one() {
+        return;
+    }
+    if is_prime(&num) {
+        factors.push(num);
+        return;
+    }
+    let divisor = (1u32..)
+        .find_map(|c| pollard_rho(&num, &BigUint::from(c)))
+        .unwrap();
+    let rest = &num / &divisor;
+    split(divisor, factors);
+    split(rest, factors);
+}
+
+/// Looks for a proper divisor with Brent's variant of Pollard's rho, using the walk
+/// x -> x^2 + c and taking one gcd per batch of steps. Returns None if the walk cycles
+/// without finding one.
+fn pollard_rho(num: &BigUint, c: &BigUint) -> Option<BigUint> {
+    const BATCH: usize = 128;
+    let step = |x: &BigUint| (x * x + c) % num;
+    let distance = |x: &BigUint, y: &BigUint| if x > y { x - y } else { y - x };
+
+    let mut y = BigUint::from(2u32);
+    let mut power = 1;
+    loop {
+        let x = y.clone();
+        for _ in 0..power {
+            y = step(&y);
+        }
+        let mut done = 0;
+        while done < power {
+            let saved = y.clone();
+            let steps = BATCH.min(power - done);
+            let mut product = BigUint::one();
+            for _ in 0..steps {
+                y = step(&y);
+                product = product * distance(&x, &y) % num;
+            }
+            let divisor = product.gcd(num);
+            if divisor == *num {
+                // the batch overshot, so walk it again a step at a time
+                let mut y = saved;
+                return (0..steps).find_map(|_| {
+                    y = step(&y);
+                    Some(distance(&x, &y).gcd(num)).filter(|d| !d.is_one() && d != num)
+                });
+            }
+            if !divisor.is_one() {
+                return Some(divisor);
+            }
+            done += steps;
+        }
+        power *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use structopt::StructOpt;
+
+    fn prime(num: u128) -> bool {
+        is_prime(&BigUint::from(num))
+    }
+
+    fn factors(num: u128) -> Vec<BigUint> {
+        let mut factors = Vec::new();
+        factorize(BigUint::from(num), &mut factors);
+        factors.sort();
+        factors
+    }
+
+    fn biguints(nums: &[u128]) -> Vec<BigUint> {
+        nums.iter().map(|num| BigUint::from(*num)).collect()
+    }
+
+    #[test]
+    fn tests_primes() {
+        for num in [2, 3, 5, 9973, 10007, 4294967291, 18446744073709551557] {
+            assert!(prime(num), "{}", num);
+        }
+        assert!(prime((1 << 127) - 1));
+        for num in [0, 1, 4, 9, 10000, 10001, 4294967297, 18446744073709551615] {
+            assert!(!prime(num), "{}", num);
+        }
+    }
+
+    #[test]
+    fn rejects_carmichael_numbers_and_strong_pseudoprimes() {
+        // Carmichael numbers pass the Fermat test in every coprime base
+        for num in [561, 1105, 1729, 41041, 825265] {
+            assert!(!prime(num), "{}", num);
+        }
+        // a strong pseudoprime to bases 2, 3, 5 and 7
+        assert!(!prime(3215031751));
+    }
+
+    #[test]
+    fn factors_composites() {
+        assert_eq!(factors(1), []);
+        assert_eq!(factors(2), biguints(&[2]));
+        assert_eq!(factors(561), biguints(&[3, 11, 17]));
+        assert_eq!(factors(41041), biguints(&[7, 11, 13, 41]));
+        assert_eq!(factors(1 << 20), biguints(&[2; 20]));
+        assert_eq!(factors(3215031751), biguints(&[151, 751, 28351]));
+    }
+
+    #[test]
+    fn factors_a_64_bit_semiprime() {
+        assert_eq!(
+            factors(4294967279 * 4294967291),
+            biguints(&[4294967279, 4294967291])
+        );
+    }
+
+    #[test]
+    fn only_factors_positive_values() {
+        let opt = Opt::from_iter(["changebase"]);
+        for value in ["0", "-12"] {
+            assert!(matches!(
+                factor(&opt, value),
+                Err(BaseError::ArgError { .. })
+            ));
+        }
+        assert!(factor(&opt, "1").is_ok());
+    }
+}
@@ -0,0 +1,616 @@
+use crate::errors::BaseError;
+
+/// Regex is a small regular expression, supporting literals, `.`, classes such as
+/// `[0-9a-f]` or `[^,]`, the escapes `\d \w \s \b` and their negations, anchors,
+/// groups, alternation and the quantifiers `* + ? {m,n}`, each optionally lazy.
+/// It is compiled to a Thompson NFA and matched a character at a time, so matching
+/// takes time linear in the text for each match and needs no recursion.
+pub struct Regex {
+    program: Vec<Inst>,
+    groups: usize,
+}
+
+/// Match holds the byte ranges of a match and of each capture group that took part in it
+pub struct Match {
+    groups: Vec<Option<(usize, usize)>>,
+}
+
+impl Match {
+    /// Returns the byte range of the first capture group, or of the whole match if the
+    /// expression has no groups
+    pub fn first_group(&self) -> Option<(usize, usize)> {
+        match self.groups.get(1) {
+            Some(group) => *group,
+            None => self.groups[0],
+        }
+    }
+}
+
+enum Node {
+    Char(char),
+    Any,
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    Start,
+    End,
+    WordBoundary(bool),
+    Group(Box<Node>, Option<usize>),
+    Concat(Vec<Node>),
+    Alternation(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+    },
+}
+
+/// Inst is an instruction of the compiled program. `Split` tries its first target
+/// before its second, which is how alternation and lazy quantifiers are ordered.
+enum Inst {
+    Char(char),
+    Any,
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    Start,
+    End,
+    WordBoundary(bool),
+    Save(usize),
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+/// Most instructions in a compiled expression, as counted repetitions are copied out
+const MAX_PROGRAM: usize = 100_000;
+
+/// Slots holds the start and end of each group, as character positions
+type Slots = Vec<Option<usize>>;
+
+impl Regex {
+    pub fn new(pattern: &str) -> Result<Regex, BaseError> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+            groups: 0,
+        };
+        let root = parser.alternation();
+        let root = match root {
+            Some(root) if parser.pos == parser.chars.len() => root,
+            _ => {
+                return Err(BaseError::ArgError {
+                    message: "Unable to parse the regular expression",
+                })
+            }
+        };
+
+        let mut program = vec![Inst::Save(0)];
+        compile(&root, &mut program)?;
+        program.push(Inst::Save(1));
+        program.push(Inst::Match);
+        Ok(Regex {
+            program,
+            groups: parser.groups,
+        })
+    }
+
+    /// Returns every non-overlapping match in the text, from left to right
+    pub fn find_all(&self, text: &str) -> Vec<Match> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut offsets: Vec<usize> = text.char_indices().map(|(offset, _)| offset).collect();
+        offsets.push(text.len());
+
+        let mut matches = Vec::new();
+        let mut start = 0;
+        while start <= chars.len() {
+            let groups = match self.find_at(&chars, start) {
+                Some(groups) => groups,
+                None => break,
+            };
+
+            let (from, to) = groups[0].unwrap();
+            // an empty match moves on by a character, so the search always advances
+            start = if to == from { to + 1 } else { to };
+            matches.push(Match {
+                groups: groups
+                    .into_iter()
+                    .map(|group| group.map(|(from, to)| (offsets[from], offsets[to])))
+                    .collect(),
+            });
+        }
+        matches
+    }
+
+    /// Finds the leftmost match starting at or after `start`, preferring the ways of
+    /// matching a backtracking matcher would try first. Threads are kept in order of
+    /// preference, and those after a thread that has matched are dropped.
+    fn find_at(&self, chars: &[char], start: usize) -> Option<Vec<Option<(usize, usize)>>> {
+        let slots = (self.groups + 1) * 2;
+        let mut seen = vec![usize::MAX; self.program.len()];
+        let mut current: Vec<(usize, Slots)> = Vec::new();
+        let mut matched = None;
+
+        for pos in start..=chars.len() {
+            if matched.is_none() {
+                self.add_thread(&mut current, &mut seen, 0, vec![None; slots], chars, pos);
+            }
+            if current.is_empty() && matched.is_some() {
+                break;
+            }
+
+            let mut next = Vec::new();
+            for (pc, slots) in current.drain(..) {
+                let consumed = match &self.program[pc] {
+                    Inst::Match => {
+                        matched = Some(slots);
+                        break;
+                    }
+                    Inst::Char(c) => chars.get(pos) == Some(c),
+                    Inst::Any => pos < chars.len() && chars[pos] != '\n',
+                    Inst::Class { ranges, negated } => chars
+                        .get(pos)
+                        .is_some_and(|c| in_class(*c, ranges) != *negated),
+                    _ => unreachable!(),
+                };
+                if consumed {
+                    self.add_thread(&mut next, &mut seen, pc + 1, slots, chars, pos + 1);
+                }
+            }
+            current = next;
+        }
+
+        matched.map(|slots| {
+            slots
+                .chunks(2)
+                .map(|pair| Some((pair[0]?, pair[1]?)))
+                .collect()
+        })
+    }
+
+    /// Follows the jumps, splits, saves and assertions from `pc` at the position, adding
+    /// the instructions that consume a character, or match, to the threads in order of
+    /// preference. An instruction already reached at this position is not added again.
+    fn add_thread(
+        &self,
+        threads: &mut Vec<(usize, Slots)>,
+        seen: &mut [usize],
+        pc: usize,
+        slots: Slots,
+        chars: &[char],
+        pos: usize,
+    ) {
+        let mut stack = vec![(pc, slots)];
+        while let Some((pc, mut slots)) = stack.pop() {
+            if seen[pc] == pos {
+                continue;
+            }
+            seen[pc] = pos;
+            match &self.program[pc] {
+                Inst::Jump(to) => stack.push((*to, slots)),
+                // the second target is pushed first, so the first is followed first
+                Inst::Split(first, second) => {
+                    stack.push((*second, slots.clone()));
+                    stack.push((*first, slots));
+                }
+                Inst::Save(slot) => {
+                    slots[*slot] = Some(pos);
+                    stack.push((pc + 1, slots));
+                }
+                Inst::Start => {
+                    if pos == 0 {
+                        stack.push((pc + 1, slots));
+                    }
+                }
+                Inst::End => {
+                    if pos == chars.len() {
+                        stack.push((pc + 1, slots));
+                    }
+                }
+                Inst::WordBoundary(expected) => {
+                    let before = pos > 0 && is_word(chars[pos - 1]);
+                    let after = pos < chars.len() && is_word(chars[pos]);
+                    if (before != after) == *expected {
+                        stack.push((pc + 1, slots));
+                    }
+                }
+                _ => threads.push((pc, slots)),
+            }
+        }
+    }
+}
+
+/// Appends the instructions matching the node to the program
+fn compile(node: &Node, program: &mut Vec<Inst>) -> Result<(), BaseError> {
+    if program.len() > MAX_PROGRAM {
+        return Err(BaseError::ArgError {
+            message: "The regular expression is too large",
+        });
+    }
+    match node {
+        Node::Char(c) => program.push(Inst::Char(*c)),
+        Node::Any => program.push(Inst::Any),
+        Node::Class { ranges, negated } => program.push(Inst::Class {
+            ranges: ranges.clone(),
+            negated: *negated,
+        }),
+        Node::Start => program.push(Inst::Start),
+        Node::End => program.push(Inst::End),
+        Node::WordBoundary(expected) => program.push(Inst::WordBoundary(*expected)),
+        Node::Group(node, index) => {
+            if let Some(index) = index {
+                program.push(Inst::Save(index * 2));
+            }
+            compile(node, program)?;
+            if let Some(index) = index {
+                program.push(Inst::Save(index * 2 + 1));
+            }
+        }
+        Node::Concat(nodes) => {
+            for node in nodes {
+                compile(node, program)?;
+            }
+        }
+        Node::Alternation(nodes) => {
+            let mut jumps = Vec::new();
+            for (index, node) in nodes.iter().enumerate() {
+                if index + 1 == nodes.len() {
+                    compile(node, program)?;
+                    break;
+                }
+                let split = program.len();
+                program.push(Inst::Split(split + 1, 0));
+                compile(node, program)?;
+                jumps.push(program.len());
+                program.push(Inst::Jump(0));
+                program[split] = Inst::Split(split + 1, program.len());
+            }
+            let end = program.len();
+            for jump in jumps {
+                program[jump] = Inst::Jump(end);
+            }
+        }
+        Node::Repeat {
+            node,
+            min,
+            max,
+            greedy,
+        } => {
+            for _ in 0..*min {
+                compile(node, program)?;
+            }
+            let split = |body, exit| {
+                if *greedy {
+                    Inst::Split(body, exit)
+                } else {
+                    Inst::Split(exit, body)
+                }
+            };
+            match max {
+                None => {
+                    let start = program.len();
+                    program.push(Inst::Jump(0));
+                    compile(node, program)?;
+                    program.push(Inst::Jump(start));
+                    program[start] = split(start + 1, program.len());
+                }
+                Some(max) => {
+                    // each optional copy can skip to the end of the repetition
+                    let mut splits = Vec::new();
+                    for _ in *min..*max {
+                        splits.push(program.len());
+                        program.push(Inst::Jump(0));
+                        compile(node, program)?;
+                    }
+                    let end = program.len();
+                    for at in splits {
+                        program[at] = split(at + 1, end);
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn in_class(c: char, ranges: &[(char, char)]) -> bool {
+    ranges.iter().any(|(low, high)| *low <= c && c <= *high)
+}
+
+fn is_word(c: char) -> bool {
+    in_class(c, WORD)
+}
+
+const DIGITS: &[(char, char)] = &[('0', '9')];
+const WORD: &[(char, char)] = &[('0', '9'), ('A', 'Z'), ('a', 'z'), ('_', '_')];
+const SPACE: &[(char, char)] = &[('\t', '\r'), (' ', ' ')];
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    groups: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn alternation(&mut self) -> Option<Node> {
+        let mut branches = vec![self.concat()?];
+        while self.eat('|') {
+            branches.push(self.concat()?);
+        }
+        if branches.len() == 1 {
+            branches.pop()
+        } else {
+            Some(Node::Alternation(branches))
+        }
+    }
+
+    fn concat(&mut self) -> Option<Node> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantified(atom)?);
+        }
+        Some(Node::Concat(nodes))
+    }
+
+    fn quantified(&mut self, node: Node) -> Option<Node> {
+        let (min, max) = match self.peek() {
+            Some('{') => match self.counts() {
+                Some(counts) => counts,
+                None => return Some(node),
+            },
+            Some(c) => {
+                let counts = match c {
+                    '*' => (0, None),
+                    '+' => (1, None),
+                    '?' => (0, Some(1)),
+                    _ => return Some(node),
+                };
+                self.pos += 1;
+                counts
+            }
+            None => return Some(node),
+        };
+        let greedy = !self.eat('?');
+        if max.is_some_and(|max| max < min) {
+            return None;
+        }
+        Some(Node::Repeat {
+            node: Box::new(node),
+            min,
+            max,
+            greedy,
+        })
+    }
+
+    /// Parses `{m}`, `{m,}` or `{m,n}`. Anything else is left to be read as a literal brace.
+    fn counts(&mut self) -> Option<(usize, Option<usize>)> {
+        let rest: String = self.chars[self.pos + 1..].iter().collect();
+        let (inner, _) = rest.split_once('}')?;
+        let counts = match inner.split_once(',') {
+            Some((min, "")) => (min.parse().ok()?, None),
+            Some((min, max)) => (min.parse().ok()?, Some(max.parse().ok()?)),
+            None => {
+                let count = inner.parse().ok()?;
+                (count, Some(count))
+            }
+        };
+        self.pos += inner.chars().count() + 2;
+        Some(counts)
+    }
+
+    fn atom(&mut self) -> Option<Node> {
+        let c = self.peek()?;
+        self.pos += 1;
+        match c {
+            '(' => {
+                let index = if self.eat('?') {
+                    if !self.eat(':') {
+                        return None;
+                    }
+                    None
+                } else {
+                    self.groups += 1;
+                    Some(self.groups)
+                };
+                let node = self.alternation()?;
+                if !self.eat(')') {
+                    return None;
+                }
+                Some(Node::Group(Box::new(node), index))
+            }
+            '[' => self.class(),
+            '.' => Some(Node::Any),
+            '^' => Some(Node::Start),
+            '$' => Some(Node::End),
+            '\\' => self.escape(),
+            '*' | '+' | '?' => None,
+            c => Some(Node::Char(c)),
+        }
+    }
+
+    fn escape(&mut self) -> Option<Node> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if let Some(ranges) = shorthand(c.to_ascii_lowercase()) {
+            return Some(Node::Class {
+                ranges: ranges.to_vec(),
+                negated: c.is_ascii_uppercase(),
+            });
+        }
+        Some(match c {
+            'b' => Node::WordBoundary(true),
+            'B' => Node::WordBoundary(false),
+            c => Node::Char(unescape(c)),
+        })
+    }
+
+    fn class(&mut self) -> Option<Node> {
+        let negated = self.eat('^');
+        let mut ranges = Vec::new();
+        loop {
+            let c = self.peek()?;
+            self.pos += 1;
+            let low = match c {
+                // a bracket straight after the opening one is a literal
+                ']' if !ranges.is_empty() => break,
+                '\\' => {
+                    let escaped = self.peek()?;
+                    self.pos += 1;
+                    if let Some(class) = shorthand(escaped) {
+                        ranges.extend_from_slice(class);
+                        continue;
+                    }
+                    unescape(escaped)
+                }
+                c => c,
+            };
+
+            let high = match (self.peek(), self.chars.get(self.pos + 1)) {
+                (Some('-'), Some(&high)) if high != ']' => {
+                    self.pos += 2;
+                    high
+                }
+                _ => low,
+            };
+            if high < low {
+                return None;
+            }
+            ranges.push((low, high));
+        }
+        Some(Node::Class { ranges, negated })
+    }
+}
+
+/// Returns the ranges of the class named by `\d`, `\w` or `\s`
+fn shorthand(c: char) -> Option<&'static [(char, char)]> {
+    match c {
+        'd' => Some(DIGITS),
+        'w' => Some(WORD),
+        's' => Some(SPACE),
+        _ => None,
+    }
+}
+
+fn unescape(c: char) -> char {
+    match c {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        c => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the text of the first group, or whole match, of each match
+    fn find(pattern: &str, text: &str) -> Vec<String> {
+        Regex::new(pattern)
+            .unwrap()
+            .find_all(text)
+            .iter()
+            .filter_map(|found| found.first_group())
+            .map(|(from, to)| text[from..to].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn literals_and_any() {
+        assert_eq!(find("ab", "xabyab"), ["ab", "ab"]);
+        assert_eq!(find("a.c", "abc a\nc"), ["abc"]);
+        assert!(find("z", "abc").is_empty());
+    }
+
+    #[test]
+    fn anchors() {
+        assert_eq!(find("^a", "aaa"), ["a"]);
+        assert_eq!(find("a$", "aaa"), ["a"]);
+        assert_eq!(find("^abc$", "abc"), ["abc"]);
+        assert!(find("^b", "ab").is_empty());
+        assert_eq!(find(r"\bab\b", "ab cab ab"), ["ab", "ab"]);
+        assert_eq!(find(r"\Bb", "ab b"), ["b"]);
+    }
+
+    #[test]
+    fn classes() {
+        assert_eq!(find("[0-9a-f]+", "xx 0xdeadbeef"), ["0", "deadbeef"]);
+        assert_eq!(find("[^,]+", "a,bc,d"), ["a", "bc", "d"]);
+        assert_eq!(find(r"\d+", "id 42 and 7"), ["42", "7"]);
+        assert_eq!(find(r"\w+", "a_1 b-2"), ["a_1", "b", "2"]);
+        assert_eq!(find(r"\S+", " x y "), ["x", "y"]);
+        assert_eq!(find("[]a]+", "]a]b"), ["]a]"]);
+        assert_eq!(find(r"[\d,]+", "1,2 3"), ["1,2", "3"]);
+    }
+
+    #[test]
+    fn alternation_prefers_the_first_branch() {
+        assert_eq!(find("cat|dog", "dog cat"), ["dog", "cat"]);
+        assert_eq!(find("a|ab", "ab"), ["a"]);
+        assert_eq!(find("(?:ab|a)c", "ac abc"), ["ac", "abc"]);
+    }
+
+    #[test]
+    fn quantifiers() {
+        assert_eq!(find("a*", "aab"), ["aa", "", ""]);
+        assert_eq!(find("a+", "baaab"), ["aaa"]);
+        assert_eq!(find("colou?r", "color colour"), ["color", "colour"]);
+        assert_eq!(find("a{2}", "aaaaa"), ["aa", "aa"]);
+        assert_eq!(find("a{2,}", "a aaa"), ["aaa"]);
+        assert_eq!(find("a{1,2}", "aaa"), ["aa", "a"]);
+        assert_eq!(find("<.+>", "<a><b>"), ["<a><b>"]);
+        assert_eq!(find("<.+?>", "<a><b>"), ["<a>", "<b>"]);
+        assert_eq!(find("a{,", "a{,"), ["a{,"]);
+    }
+
+    #[test]
+    fn groups() {
+        assert_eq!(
+            find("id=(0x[0-9a-f]+)", "id=0xff id=0x10"),
+            ["0xff", "0x10"]
+        );
+        assert_eq!(find("(a)|b", "b"), Vec::<String>::new());
+        assert_eq!(find("(?:x)(y)", "xy"), ["y"]);
+        assert_eq!(find("(a|b)+", "abab"), ["b"]);
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        for pattern in &["(", "a)", "*a", "[a", "[z-a]", "a{3,1}", "(?a)"] {
+            assert!(Regex::new(pattern).is_err(), "{} should not parse", pattern);
+        }
+        assert!(Regex::new("(a{1000}){1000}").is_err());
+    }
+
+    #[test]
+    fn pathological_patterns() {
+        let text = "a".repeat(40);
+        assert!(find("(a*)*b", &text).is_empty());
+        assert!(find("(a|aa)+$x", &text).is_empty());
+        assert_eq!(find("(a+)+", &text), vec![text]);
+
+        let line = "ab".repeat(200_000);
+        assert_eq!(find("(?:ab)+", &line).len(), 1);
+        assert_eq!(find(r"(\w+)$", &line), vec![line]);
+    }
+}
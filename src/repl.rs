@@ -0,0 +1,55 @@
+use crate::base::Value;
+use crate::errors::BaseError;
+use crate::opts::Opt;
+use crate::{format_output, parse_number, report_error, Number, Parsed};
+use std::io::{self, BufRead, Write};
+
+/// Runs an interactive prompt that converts each entered value or expression.
+/// The previous integer result can be referred to as `_` or `last`.
+pub fn run(opt: &Opt) -> Result<(), BaseError> {
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let mut last: Option<Value> = None;
+
+    let mut line = String::new();
+    loop {
+        print!("> ");
+        io::stdout().flush().map_err(|_| BaseError::IoError {
+            message: "Unable to write to stdout",
+        })?;
+
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|_| BaseError::IoError {
+                message: "Unable to read from stdin",
+            })?;
+        if read == 0 {
+            println!();
+            return Ok(());
+        }
+
+        let value = line.trim();
+        match value {
+            "" => continue,
+            "exit" | "quit" => return Ok(()),
+            _ => {}
+        }
+
+        match evaluate(opt, value, last.as_ref()).map_err(|e| e.in_input(value)) {
+            Ok((output, num)) => {
+                println!("{}", output);
+                if let Number::Integer(num) = num.num {
+                    last = Some(num);
+                }
+            }
+            Err(e) => report_error(e),
+        }
+    }
+}
+
+fn evaluate(opt: &Opt, value: &str, last: Option<&Value>) -> Result<(String, Parsed), BaseError> {
+    let parsed = parse_number(opt, value, last)?;
+    let output = format_output(opt, &parsed)?;
+    Ok((output, parsed))
+}
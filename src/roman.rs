@@ -0,0 +1,145 @@
+use crate::errors::BaseError;
+use crate::opts::Base;
+use num::{bigint::BigInt, ToPrimitive};
+
+const NUMERALS: [(u32, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+/// Combining overline placed after a numeral to multiply it by a thousand
+const VINCULUM: char = '\u{305}';
+
+/// Largest value written with the classical numerals
+const MAX: u32 = 3999;
+
+/// Largest value written with overlined thousands
+const VINCULUM_MAX: u32 = 3_999_999;
+
+/// Writes the value as Roman numerals. With `vinculum`, values above 3999 are written
+/// with the thousands overlined, e.g. V̅ for 5000.
+pub fn to_roman(value: &BigInt, vinculum: bool) -> Result<String, BaseError> {
+    let max = if vinculum { VINCULUM_MAX } else { MAX };
+    let value = match value.to_u32() {
+        Some(value) if value >= 1 && value <= max => value,
+        _ => {
+            return Err(BaseError::OverflowError {
+                message:
+                    "Roman numerals only represent 1 to 3999, or up to 3999999 with --vinculum",
+            })
+        }
+    };
+
+    if value <= MAX {
+        return Ok(numerals(value));
+    }
+    let thousands: String = numerals(value / 1000)
+        .chars()
+        .flat_map(|c| [c, VINCULUM])
+        .collect();
+    Ok(thousands + &numerals(value % 1000))
+}
+
+fn numerals(mut value: u32) -> String {
+    let mut result = String::new();
+    for (amount, numeral) in NUMERALS {
+        while value >= amount {
+            result.push_str(numeral);
+            value -= amount;
+        }
+    }
+    result
+}
+
+/// Reads Roman numerals in either case, including overlined thousands. Only the
+/// canonical subtractive form is accepted, so IIII or IC are rejected.
+pub fn parse(value: &str) -> Result<BigInt, BaseError> {
+    let error = BaseError::InvalidFormat { base: Base::Roman };
+
+    let mut amounts = Vec::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        let amount = match c.to_ascii_uppercase() {
+            'I' => 1,
+            'V' => 5,
+            'X' => 10,
+            'L' => 50,
+            'C' => 100,
+            'D' => 500,
+            'M' => 1000,
+            _ => return Err(error),
+        };
+        if chars.peek() == Some(&VINCULUM) {
+            chars.next();
+            amounts.push(amount * 1000);
+        } else {
+            amounts.push(amount);
+        }
+    }
+
+    let mut total: u64 = 0;
+    for (index, amount) in amounts.iter().enumerate() {
+        match amounts.get(index + 1) {
+            Some(next) if next > amount => total = total.wrapping_sub(*amount as u64),
+            _ => total = total.wrapping_add(*amount as u64),
+        }
+    }
+
+    let total = BigInt::from(total);
+    match to_roman(&total, true) {
+        Ok(canonical) if canonical == value.to_uppercase() => Ok(total),
+        _ => Err(error),
+    }
+}
+
+pub fn is_valid(value: &str) -> bool {
+    parse(value).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roman(value: u32, vinculum: bool) -> String {
+        to_roman(&BigInt::from(value), vinculum).unwrap()
+    }
+
+    #[test]
+    fn writes_numerals() {
+        assert_eq!(roman(1994, false), "MCMXCIV");
+        assert_eq!(roman(3999, false), "MMMCMXCIX");
+        assert_eq!(roman(5000, true), "V\u{305}");
+        assert_eq!(roman(4001, true), "I\u{305}V\u{305}I");
+        assert!(to_roman(&BigInt::from(0), true).is_err());
+        assert!(to_roman(&BigInt::from(-1), true).is_err());
+        assert!(to_roman(&BigInt::from(4000), false).is_err());
+        assert!(to_roman(&BigInt::from(4_000_000), true).is_err());
+    }
+
+    #[test]
+    fn round_trips() {
+        for value in (1..=MAX).chain([4000, 12_345, VINCULUM_MAX]) {
+            let numerals = roman(value, true);
+            assert_eq!(parse(&numerals).unwrap(), BigInt::from(value), "{}", value);
+        }
+        assert_eq!(parse("mcmxciv").unwrap(), BigInt::from(1994));
+    }
+
+    #[test]
+    fn rejects_non_canonical_numerals() {
+        for value in ["", "IIII", "IC", "VV", "MMMM", "XM", "I\u{305}", "12", "A"] {
+            assert!(parse(value).is_err(), "{:?}", value);
+        }
+    }
+}
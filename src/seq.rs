@@ -0,0 +1,44 @@
+use crate::base::Value;
+use crate::errors::BaseError;
+use crate::opts::Opt;
+use crate::{format_number, parse_number, Number};
+use num::{bigint::BigInt, One, Signed, Zero};
+use std::io::{self, BufWriter, Write};
+
+/// Prints the numbers from start to end inclusive, counting by step, in the output base
+/// or otherwise the base of start. As with seq, a step away from end prints nothing
+/// after start.
+pub fn run(opt: &Opt, start: &str, end: &str, step: Option<&str>) -> Result<(), BaseError> {
+    let read = |value: &str| {
+        parse_number(opt, value, None)
+            .and_then(|parsed| Ok((parsed.integer()?.clone().into_inner(), parsed)))
+            .map_err(|e| e.in_input(value))
+    };
+    let (mut num, mut first) = read(start)?;
+    let (end, _) = read(end)?;
+    let step = match step {
+        Some(step) => read(step)?.0,
+        None => BigInt::one(),
+    };
+    if step.is_zero() {
+        return Err(BaseError::ArgError {
+            message: "The step of seq must not be zero",
+        });
+    }
+
+    let output = opt.get_output().unwrap_or_else(|| first.base.clone());
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    while (step.is_positive() && num <= end) || (step.is_negative() && num >= end) {
+        // each number keeps the width start was written in
+        first.num = Number::Integer(Value::new(num.clone()));
+        let line = format_number(opt, &first, output.clone())?;
+        writeln!(writer, "{}", line).map_err(|_| BaseError::IoError {
+            message: "Unable to write to stdout",
+        })?;
+        num += &step;
+    }
+    writer.flush().map_err(|_| BaseError::IoError {
+        message: "Unable to write to stdout",
+    })
+}
@@ -0,0 +1,279 @@
+use crate::base::{detect_base, explain_detection};
+use crate::errors::BaseError;
+use crate::json::{self, Json};
+use crate::opts::{Base, ColorWhen, Opt};
+use crate::{error_message, format_number, info, parse_number, Number, Parsed};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+/// Most bytes read for the request line and headers of an HTTP request
+const MAX_REQUEST: u64 = 16 * 1024;
+
+/// Most bits of a value that is answered, as writing larger values in decimal is slow
+/// enough to tie up the server
+const MAX_BITS: u64 = 1 << 16;
+
+/// Stack of each connection's thread, with room for the deepest expression allowed
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Answers requests read from stdin, one JSON object per line, with one JSON object per
+/// line on stdout, so an editor can keep one process running. A request names its
+/// `method`, one of `convert`, `detect` or `info`, and gives the `value`, with `from`
+/// and `to` bases for `convert`. Its `id`, if any, is copied to the response, which
+/// holds either the `result` or an `error` message.
+pub fn run_stdio(opt: &Opt) -> Result<(), BaseError> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+    for line in stdin.lock().lines() {
+        let line = line.map_err(|_| BaseError::IoError {
+            message: "Unable to read from stdin",
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // each response is flushed straight away, as the client waits for it
+        writeln!(writer, "{}", respond(opt, &line))
+            .and_then(|_| writer.flush())
+            .map_err(|_| BaseError::IoError {
+                message: "Unable to write to stdout",
+            })?;
+    }
+    Ok(())
+}
+
+/// Answers HTTP GET requests on the address, each connection on its own thread.
+/// `/convert` takes the `value` and optional `from` and `to` bases as query parameters,
+/// and `/all` writes the value in every shown base. `/detect` and `/info` answer as
+/// their methods do over stdin. The body is `{"result":...}`, or `{"error":"..."}`
+/// with status 400 for a value that cannot be converted. Requests over MAX_REQUEST
+/// bytes and values over MAX_BITS are refused, so no one request can tie up the server.
+pub fn run_http(opt: &Opt, address: &str) -> Result<(), BaseError> {
+    let listener = TcpListener::bind(address).map_err(|_| BaseError::IoError {
+        message: "Unable to listen on the address",
+    })?;
+    for stream in listener.incoming() {
+        // a failed connection only concerns its client
+        let Ok(stream) = stream else { continue };
+        let opt = opt.clone();
+        // a request that panics only ends its own thread
+        let _ = thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn(move || serve_connection(&opt, stream));
+    }
+    Ok(())
+}
+
+/// Reads one request from the connection and writes its response, closing it after
+fn serve_connection(opt: &Opt, mut stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    let mut reader = BufReader::new(stream.try_clone()?.take(MAX_REQUEST));
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // the headers are not needed, but are read so the client is not reset mid-request.
+    // A body is never read, as only GET requests are answered.
+    let mut header = String::new();
+    let mut complete = request.ends_with('\n');
+    while complete {
+        header.clear();
+        reader.read_line(&mut header)?;
+        // a line cut short was cut by the limit, and an empty one is the end of input
+        complete = header.is_empty() || header.ends_with('\n');
+        if header.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut words = request.split_whitespace();
+    let (status, body) = match (words.next(), words.next()) {
+        _ if !complete => (
+            "431 Request Header Fields Too Large",
+            error("The request is too large"),
+        ),
+        (Some("GET"), Some(target)) => route(opt, target),
+        (Some(_), Some(_)) => ("405 Method Not Allowed", error("Only GET is supported")),
+        _ => ("400 Bad Request", error("Unable to parse the request")),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}\n",
+        status,
+        body.len() + 1,
+        body
+    )?;
+    stream.flush()
+}
+
+/// Returns the status and body answering the path and query of a request
+fn route(opt: &Opt, target: &str) -> (&'static str, String) {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let params: Vec<(String, String)> = query
+        .split('&')
+        .filter_map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            Some((percent_decode(name)?, percent_decode(value)?))
+        })
+        .collect();
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    };
+
+    let result = match path {
+        "/convert" => answer(opt, "convert", param),
+        "/all" => answer(opt, "convert", |name| match name {
+            "to" => None,
+            name => param(name),
+        }),
+        "/detect" => answer(opt, "detect", param),
+        "/info" => answer(opt, "info", param),
+        _ => return ("404 Not Found", error("Unknown path")),
+    };
+    match result {
+        Ok(result) => ("200 OK", format!("{{\"result\":{}}}", result)),
+        Err(message) => ("400 Bad Request", error(&message)),
+    }
+}
+
+fn error(message: &str) -> String {
+    format!("{{\"error\":{}}}", json::string(message))
+}
+
+/// Decodes `%XX` escapes and `+` for spaces in a query parameter, returning None for
+/// a broken escape or bytes that are not UTF-8
+fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.bytes();
+    while let Some(byte) = rest.next() {
+        bytes.push(match byte {
+            b'+' => b' ',
+            b'%' => {
+                let digits = [rest.next()?, rest.next()?];
+                u8::from_str_radix(std::str::from_utf8(&digits).ok()?, 16).ok()?
+            }
+            byte => byte,
+        });
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Returns the response to a request line
+fn respond(opt: &Opt, request: &str) -> String {
+    let fields = match json::parse_object(request) {
+        Some(fields) => fields,
+        None => return response("null", Err("Unable to parse the request".to_string())),
+    };
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+    };
+    let id = field("id")
+        .map(Json::render)
+        .unwrap_or_else(|| "null".to_string());
+    let result = match field("method") {
+        Some(method) => answer(opt, method.as_str(), |name| {
+            field(name).map(|value| value.as_str().to_string())
+        }),
+        None => Err("The request has no method".to_string()),
+    };
+    response(&id, result)
+}
+
+fn response(id: &str, result: Result<String, String>) -> String {
+    match result {
+        Ok(result) => format!("{{\"id\":{},\"result\":{}}}", id, result),
+        Err(message) => format!("{{\"id\":{},\"error\":{}}}", id, json::string(&message)),
+    }
+}
+
+/// Answers a method with the parameters looked up by `param`, returning the result as
+/// JSON or an error message
+pub fn answer(
+    opt: &Opt,
+    method: &str,
+    param: impl Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let value = param("value").ok_or_else(|| "The request has no value".to_string())?;
+    let base = |name: &str| -> Result<Option<Base>, String> {
+        param(name)
+            .map(|base| base.parse().map_err(|_| format!("Unknown base {}", base)))
+            .transpose()
+    };
+    // diagnostics would only interleave with the responses of other requests, and
+    // color escapes do not belong in JSON
+    let mut opt = opt.clone();
+    opt.quiet = true;
+    opt.color = ColorWhen::Never;
+    opt.input = base("from")?;
+    let message = |e: BaseError| error_message(&e);
+    let parse = |value: &str| -> Result<Parsed, String> {
+        let parsed = parse_number(&opt, value, None).map_err(message)?;
+        let bits = match &parsed.num {
+            Number::Integer(num) => num.bits(),
+            Number::Fraction(fraction) => fraction.bits(),
+        };
+        if bits > MAX_BITS {
+            return Err("The value is too large, at more than 2^16 bits".to_string());
+        }
+        Ok(parsed)
+    };
+
+    match method {
+        "convert" => {
+            let parsed = parse(&value)?;
+            match base("to")? {
+                Some(to) => Ok(json::string(
+                    &format_number(&opt, &parsed, to).map_err(message)?,
+                )),
+                // bases the value does not fit, such as IPv4 for large values, are left out
+                None => Ok(json::object(
+                    &opt.shown_bases()
+                        .into_iter()
+                        .filter_map(|base| {
+                            let digits = format_number(&opt, &parsed, base.clone()).ok()?;
+                            Some((base.name(), digits))
+                        })
+                        .collect::<Vec<_>>(),
+                )),
+            }
+        }
+        "detect" => {
+            let base = detect_base(&value, opt.detect_strategy()).map_err(message)?;
+            Ok(json::object(&[
+                ("base", base.name().to_string()),
+                ("explanation", explain_detection(&value, base)),
+            ]))
+        }
+        "info" => {
+            let parsed = parse(&value)?;
+            let width = opt.width.or(parsed.width);
+            let num = parsed
+                .integer()
+                .and_then(|num| info::unsigned(num, width))
+                .map_err(message)?;
+            let description = info::describe(&num, width);
+            let fields: Vec<(String, String)> = description
+                .lines()
+                .filter_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    Some((
+                        name.to_lowercase().replace(' ', "_"),
+                        value.trim().to_string(),
+                    ))
+                })
+                .collect();
+            let fields: Vec<(&str, String)> = fields
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.clone()))
+                .collect();
+            Ok(json::object(&fields))
+        }
+        method => Err(format!("Unknown method {}", method)),
+    }
+}
@@ -0,0 +1,82 @@
+use crate::errors::BaseError;
+use crate::opts::Base;
+use num::{bigint::BigInt, Integer, Num, Signed, Zero};
+
+/// Unit prefixes in increasing order of magnitude, starting from kilo
+const PREFIXES: [char; 8] = ['K', 'M', 'G', 'T', 'P', 'E', 'Z', 'Y'];
+
+/// Returns the size of one unit with the given power, in powers of 1000 if `si` is set
+/// and 1024 otherwise
+fn unit(power: usize, si: bool) -> BigInt {
+    let step = if si { 1000u32 } else { 1024 };
+    num::pow(BigInt::from(step), power)
+}
+
+/// Writes a number of bytes with two decimal places in the largest unit it reaches,
+/// e.g. 1.50 GiB, or 1.50 GB with `si`
+pub fn to_size(value: &BigInt, si: bool) -> Result<String, BaseError> {
+    if value.is_negative() {
+        return Err(BaseError::OverflowError {
+            message: "Sizes can not be negative",
+        });
+    }
+
+    let power = (1..=PREFIXES.len())
+        .take_while(|power| *value >= unit(*power, si))
+        .last();
+    let power = match power {
+        Some(power) => power,
+        None => return Ok(format!("{} B", value)),
+    };
+
+    // round to the nearest hundredth of the unit, halves up
+    let unit = unit(power, si);
+    let hundredths = (value * 200u32 + &unit) / (unit * 2u32);
+    let (integer, fraction) = hundredths.div_rem(&BigInt::from(100u32));
+    let prefix = match (si, PREFIXES[power - 1]) {
+        (true, 'K') => "k".to_string(),
+        (true, prefix) => prefix.to_string(),
+        (false, prefix) => format!("{}i", prefix),
+    };
+    Ok(format!("{}.{:0>2} {}B", integer, fraction, prefix))
+}
+
+/// Reads a size such as 4K, 2MiB or 1.5GB, in either case. Units with an i are always
+/// powers of 1024; other units are powers of 1000 if `si` is set and 1024 otherwise.
+/// The size must be a whole number of bytes.
+pub fn parse(value: &str, si: bool) -> Result<BigInt, BaseError> {
+    parse_size(value, si).ok_or(BaseError::InvalidFormat { base: Base::Size })
+}
+
+fn parse_size(value: &str, si: bool) -> Option<BigInt> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, suffix) = value.split_at(split);
+    let suffix = suffix.trim().to_ascii_uppercase();
+
+    let suffix = suffix.strip_suffix('B').unwrap_or(&suffix);
+    let (suffix, si) = match suffix.strip_suffix('I') {
+        Some(prefix) if !prefix.is_empty() => (prefix, false),
+        _ => (suffix, si),
+    };
+    let power = match suffix.chars().collect::<Vec<char>>()[..] {
+        [] => 0,
+        [prefix] => PREFIXES.iter().position(|p| *p == prefix)? + 1,
+        _ => return None,
+    };
+
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if integer.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    let digits = BigInt::from_str_radix(&format!("0{}{}", integer, fraction), 10).ok()?;
+    let scale = num::pow(BigInt::from(10u32), fraction.len());
+    let (bytes, remainder) = (digits * unit(power, si)).div_rem(&scale);
+    if remainder.is_zero() {
+        Some(bytes)
+    } else {
+        None
+    }
+}
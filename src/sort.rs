@@ -0,0 +1,30 @@
+use crate::errors::BaseError;
+use crate::opts::Opt;
+use crate::{format_integer, read_numbers};
+use std::io::{self, BufWriter, Write};
+
+/// Prints the numbers read in numeric order, keeping the order of equal numbers
+pub fn run(opt: &Opt, reverse: bool) -> Result<(), BaseError> {
+    let mut numbers = read_numbers(opt)?;
+    if reverse {
+        numbers.sort_by(|(_, a), (_, b)| b.cmp(a));
+    } else {
+        numbers.sort_by(|(_, a), (_, b)| a.cmp(b));
+    }
+
+    let output = opt.get_output();
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    for (value, num) in numbers {
+        let line = match &output {
+            Some(base) => format_integer(opt, num, base.clone())?,
+            None => value,
+        };
+        writeln!(writer, "{}", line).map_err(|_| BaseError::IoError {
+            message: "Unable to write to stdout",
+        })?;
+    }
+    writer.flush().map_err(|_| BaseError::IoError {
+        message: "Unable to write to stdout",
+    })
+}
@@ -0,0 +1,46 @@
+use crate::base::Value;
+use crate::errors::BaseError;
+use crate::fraction::Fraction;
+use crate::json;
+use crate::opts::{Base, Opt};
+use crate::{format_integer, format_number, read_numbers, Number, Parsed};
+use num::{bigint::BigInt, rational::BigRational};
+
+/// Prints the count, minimum, maximum, sum and mean of the numbers read, in the output
+/// base or decimal. The sum and mean are exact, with the mean written to --precision
+/// digits after the radix point.
+pub fn run(opt: &Opt) -> Result<(), BaseError> {
+    let numbers: Vec<BigInt> = read_numbers(opt)?
+        .into_iter()
+        .map(|(_, num)| num.into_inner())
+        .collect();
+    let output = opt.get_output().unwrap_or(Base::Dec);
+
+    let mut fields = vec![("count", numbers.len().to_string())];
+    if let (Some(min), Some(max)) = (numbers.iter().min(), numbers.iter().max()) {
+        let sum: BigInt = numbers.iter().sum();
+        let mean = Parsed {
+            base: Base::Dec,
+            num: Number::Fraction(Fraction::new(BigRational::new(
+                sum.clone(),
+                BigInt::from(numbers.len()),
+            ))),
+            width: None,
+            signed: false,
+        };
+        let format = |num: &BigInt| format_integer(opt, Value::new(num.clone()), output.clone());
+        fields.push(("min", format(min)?));
+        fields.push(("max", format(max)?));
+        fields.push(("sum", format(&sum)?));
+        fields.push(("mean", format_number(opt, &mean, output.clone())?));
+    }
+
+    if opt.json {
+        println!("{}", json::object(&fields));
+    } else {
+        for (name, value) in fields {
+            println!("{}: {}", name, value);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use crate::base::radix;
+use crate::errors::BaseError;
+use crate::opts::{Base, Opt, TableFormat};
+use crate::{format_number, parse_number, Number, Parsed};
+
+/// Renders one table row, escaping cells that contain the format's delimiters
+pub fn row(format: &TableFormat, cells: &[String]) -> String {
+    match format {
+        TableFormat::Md => {
+            let cells: Vec<String> = cells.iter().map(|cell| cell.replace('|', "\\|")).collect();
+            format!("| {} |", cells.join(" | "))
+        }
+        TableFormat::Csv => {
+            let cells: Vec<String> = cells.iter().map(|cell| csv_cell(cell)).collect();
+            cells.join(",")
+        }
+        TableFormat::Tsv => {
+            let cells: Vec<String> = cells.iter().map(|cell| cell.replace('\t', " ")).collect();
+            cells.join("\t")
+        }
+        TableFormat::Aligned => cells.join("  "),
+    }
+}
+
+/// Renders the values as a table with a row per value and a column per base. Digits of
+/// positional bases other than decimal are zero-padded to the longest integer part in
+/// their column, and cells are right-aligned on the integer part and aligned on the radix
+/// point, so bit patterns and signs line up.
+pub fn aligned(opt: &Opt, values: &[String]) -> Result<String, BaseError> {
+    let parsed = values
+        .iter()
+        .map(|value| parse_number(opt, value, None).map_err(|e| e.in_input(value)))
+        .collect::<Result<Vec<Parsed>, BaseError>>()?;
+
+    let mut columns = vec![std::iter::once("input".to_string())
+        .chain(values.iter().cloned())
+        .collect::<Vec<String>>()];
+    for base in opt.output_bases() {
+        let mut opt = opt.clone();
+        let positional = radix(base.clone()).is_some();
+        // decimal is padded with spaces by the alignment below
+        if positional && base != Base::Dec {
+            let longest = parsed
+                .iter()
+                .filter_map(|parsed| match &parsed.num {
+                    Number::Integer(num) => num.to_base(base.clone()).ok(),
+                    Number::Fraction(num) => num.to_base(base.clone(), opt.precision).ok(),
+                })
+                .map(|digits| integer_part(digits.trim_start_matches('-')).len())
+                .max()
+                .unwrap_or(0);
+            opt.pad = Some(opt.pad.unwrap_or(0).max(longest));
+        }
+
+        let cells = parsed
+            .iter()
+            .map(|parsed| format_number(&opt, parsed, base.clone()))
+            .collect::<Result<Vec<String>, BaseError>>()?;
+        let cells = if positional {
+            align_points(&cells)
+        } else {
+            cells
+        };
+        columns.push(
+            std::iter::once(base.name().to_string())
+                .chain(cells)
+                .collect(),
+        );
+    }
+
+    let widths: Vec<usize> = columns
+        .iter()
+        .map(|column| {
+            column
+                .iter()
+                .map(|cell| display_width(cell))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let lines: Vec<String> = (0..=values.len())
+        .map(|index| {
+            let cells: Vec<String> = columns
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(column, (cells, width))| {
+                    // padded by hand, as formatting would count the bytes of color escapes
+                    let cell = &cells[index];
+                    let padding = " ".repeat(width - display_width(cell));
+                    match column {
+                        0 => format!("{}{}", cell, padding),
+                        _ => format!("{}{}", padding, cell),
+                    }
+                })
+                .collect();
+            row(&TableFormat::Aligned, &cells).trim_end().to_string()
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// Returns the digits before the radix point
+fn integer_part(digits: &str) -> &str {
+    digits.split('.').next().unwrap_or(digits)
+}
+
+/// Pads the cells of a column so their integer parts are right-aligned and their
+/// fractional parts left-aligned, lining up the radix points
+fn align_points(cells: &[String]) -> Vec<String> {
+    let split: Vec<(&str, &str)> = cells
+        .iter()
+        .map(|cell| cell.split_at(cell.find('.').unwrap_or(cell.len())))
+        .collect();
+    let whole = split
+        .iter()
+        .map(|(whole, _)| display_width(whole))
+        .max()
+        .unwrap_or(0);
+    let fractional = split
+        .iter()
+        .map(|(_, fractional)| display_width(fractional))
+        .max()
+        .unwrap_or(0);
+    split
+        .iter()
+        .map(|(w, f)| {
+            format!(
+                "{}{}{}{}",
+                " ".repeat(whole - display_width(w)),
+                w,
+                f,
+                " ".repeat(fractional - display_width(f))
+            )
+        })
+        .collect()
+}
+
+/// Returns the number of characters the cell takes up on a terminal, leaving out the
+/// escape sequences that color digit groups
+fn display_width(cell: &str) -> usize {
+    let mut width = 0;
+    let mut chars = cell.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // a sequence such as `\x1b[36m` ends with its first letter
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+/// Renders the header row, followed by the separator row of a Markdown table
+pub fn header(format: &TableFormat, columns: &[String]) -> String {
+    let header = row(format, columns);
+    match format {
+        TableFormat::Md => {
+            let rule = vec!["---".to_string(); columns.len()];
+            format!("{}\n{}", header, row(format, &rule))
+        }
+        _ => header,
+    }
+}
+
+/// Quotes a CSV cell containing a comma, quote or newline, doubling any quotes
+fn csv_cell(cell: &str) -> String {
+    if cell.contains([',', '"', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_width_leaves_out_color() {
+        assert_eq!(display_width("ff"), 2);
+        assert_eq!(display_width("\x1b[36m0000\x1b[0m1111"), 8);
+    }
+
+    #[test]
+    fn aligns_radix_points() {
+        let cells = ["-1.5".to_string(), "255".to_string(), "16".to_string()];
+        assert_eq!(align_points(&cells), vec![" -1.5", "255  ", " 16  "]);
+    }
+}
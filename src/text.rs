@@ -0,0 +1,99 @@
+use crate::base::{radix, strip_base_prefix, Value};
+use crate::bytes::format_bytes;
+use crate::command::TextOp;
+use crate::errors::{BaseError, Syntax};
+use crate::opts::{Base, Opt};
+use num::bigint::BigInt;
+
+/// Runs a text encoding or decoding
+pub fn run(opt: &Opt, op: &TextOp) -> Result<(), BaseError> {
+    match op {
+        TextOp::Encode { text } => println!("{}", encode(opt, text)?),
+        TextOp::Decode { value } => println!("{}", decode(opt, value)?),
+    }
+    Ok(())
+}
+
+/// Writes the UTF-8 bytes of the text in the output base or hex, or in the --bytes format
+fn encode(opt: &Opt, text: &str) -> Result<String, BaseError> {
+    if let Some(format) = &opt.bytes {
+        return Ok(format_bytes(text.as_bytes(), format));
+    }
+
+    let output = opt.get_output().unwrap_or(Base::Hex);
+    let bytes = text
+        .bytes()
+        .map(|byte| Value::new(BigInt::from(byte)).to_padded_base(output.clone(), 8))
+        .collect::<Result<Vec<String>, BaseError>>()?;
+    // hex bytes read well run together, other bases are separated
+    let separator = if output == Base::Hex { "" } else { " " };
+    Ok(bytes.join(separator))
+}
+
+/// Reads bytes written in hex, or binary with --ib, and shows them as UTF-8 text with
+/// control characters and invalid bytes escaped
+fn decode(opt: &Opt, value: &str) -> Result<String, BaseError> {
+    let input = opt.explicit_input().unwrap_or(Base::Hex);
+    let digits_per_byte = match input {
+        Base::Hex => 2,
+        Base::Bin => 8,
+        _ => {
+            return Err(BaseError::ArgError {
+                message: "Text can only be decoded from hex or binary bytes",
+            })
+        }
+    };
+
+    let digits: String = strip_base_prefix(value, &input)
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '_')
+        .collect();
+    if digits.is_empty() || !digits.len().is_multiple_of(digits_per_byte) {
+        return Err(BaseError::InvalidSyntax {
+            syntax: Syntax::TextBytes,
+        });
+    }
+    let bytes = digits
+        .as_bytes()
+        .chunks(digits_per_byte)
+        .map(|chunk| {
+            let chunk = std::str::from_utf8(chunk).unwrap_or("");
+            u8::from_str_radix(chunk, radix(input.clone()).unwrap()).ok()
+        })
+        .collect::<Option<Vec<u8>>>()
+        .ok_or(BaseError::InvalidSyntax {
+            syntax: Syntax::Text,
+        })?;
+    Ok(escape_text(&bytes))
+}
+
+/// Shows UTF-8 text, escaping control characters, backslashes and invalid bytes
+pub fn escape_text(bytes: &[u8]) -> String {
+    let mut result = String::new();
+    let mut rest = bytes;
+    while !rest.is_empty() {
+        let (valid, invalid) = match std::str::from_utf8(rest) {
+            Ok(text) => (text, &[][..]),
+            Err(e) => {
+                let (valid, invalid) = rest.split_at(e.valid_up_to());
+                let len = e.error_len().unwrap_or(invalid.len());
+                (std::str::from_utf8(valid).unwrap(), &invalid[..len])
+            }
+        };
+        for c in valid.chars() {
+            match c {
+                '\n' => result.push_str("\\n"),
+                '\r' => result.push_str("\\r"),
+                '\t' => result.push_str("\\t"),
+                '\\' => result.push_str("\\\\"),
+                c if c.is_control() => result.push_str(&format!("\\u{{{:x}}}", c as u32)),
+                c => result.push(c),
+            }
+        }
+        for byte in invalid {
+            result.push_str(&format!("\\x{:02x}", byte));
+        }
+        rest = &rest[valid.len() + invalid.len()..];
+    }
+    result
+}
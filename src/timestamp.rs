@@ -0,0 +1,121 @@
+use crate::errors::BaseError;
+use crate::opts::TimeUnit;
+use num::{bigint::BigInt, Integer, ToPrimitive};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Returns the number of the unit in a second, and the digits of its fraction of a second
+fn scale(unit: &TimeUnit) -> (u32, usize) {
+    match unit {
+        TimeUnit::S => (1, 0),
+        TimeUnit::Ms => (1_000, 3),
+        TimeUnit::Us => (1_000_000, 6),
+        TimeUnit::Ns => (1_000_000_000, 9),
+    }
+}
+
+/// Writes the Unix timestamp as an ISO 8601 UTC datetime, e.g. 2024-01-12T21:45:39Z.
+/// Timestamps in milliseconds, microseconds or nanoseconds keep their fraction of a second.
+pub fn to_datetime(value: &BigInt, unit: &TimeUnit) -> Result<String, BaseError> {
+    let (per_second, places) = scale(unit);
+    let (seconds, fraction) = value.div_mod_floor(&BigInt::from(per_second));
+    let seconds = seconds
+        .to_i64()
+        .filter(|seconds| seconds.unsigned_abs() < 1 << 48)
+        .ok_or(BaseError::OverflowError {
+            message: "Timestamp is too far from 1970 to show as a date",
+        })?;
+
+    let (days, time) = (
+        seconds.div_euclid(SECONDS_PER_DAY),
+        seconds.rem_euclid(SECONDS_PER_DAY),
+    );
+    let (year, month, day) = civil_from_days(days);
+    let mut datetime = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    );
+    if places > 0 {
+        datetime.push_str(&format!(".{:0>width$}", fraction, width = places));
+    }
+    datetime.push('Z');
+    Ok(datetime)
+}
+
+/// Converts days since 1970-01-01 to a proleptic Gregorian year, month and day, counting
+/// in 400 year eras that start on March 1st so leap days fall at the end of each year
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(value: i64, unit: TimeUnit) -> String {
+        to_datetime(&BigInt::from(value), &unit).unwrap()
+    }
+
+    #[test]
+    fn writes_known_dates() {
+        for (value, expected) in [
+            (0, "1970-01-01T00:00:00Z"),
+            (951782400, "2000-02-29T00:00:00Z"),
+            (951868799, "2000-02-29T23:59:59Z"),
+            (1700000000, "2023-11-14T22:13:20Z"),
+            (-1, "1969-12-31T23:59:59Z"),
+            (-14182940, "1969-07-20T20:17:40Z"),
+            (-62135596800, "0001-01-01T00:00:00Z"),
+            (253402300799, "9999-12-31T23:59:59Z"),
+        ] {
+            assert_eq!(datetime(value, TimeUnit::S), expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn keeps_fractions_of_a_second() {
+        assert_eq!(datetime(0, TimeUnit::Ms), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            datetime(951782400123, TimeUnit::Ms),
+            "2000-02-29T00:00:00.123Z"
+        );
+        assert_eq!(
+            datetime(951782400000001, TimeUnit::Us),
+            "2000-02-29T00:00:00.000001Z"
+        );
+        assert_eq!(
+            datetime(1700000000123456789, TimeUnit::Ns),
+            "2023-11-14T22:13:20.123456789Z"
+        );
+        // negative timestamps round down to the second before, with a positive fraction
+        assert_eq!(datetime(-1, TimeUnit::Ms), "1969-12-31T23:59:59.999Z");
+        assert_eq!(datetime(-1, TimeUnit::Ns), "1969-12-31T23:59:59.999999999Z");
+    }
+
+    #[test]
+    fn rejects_distant_timestamps() {
+        let distant = BigInt::from(1u64 << 48);
+        assert!(to_datetime(&distant, &TimeUnit::S).is_err());
+        assert!(to_datetime(&-distant, &TimeUnit::S).is_err());
+        assert!(to_datetime(&(BigInt::from(u64::MAX) << 64), &TimeUnit::Ns).is_err());
+    }
+}
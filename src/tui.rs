@@ -0,0 +1,150 @@
+use crate::base::{default_group_size, group};
+use crate::errors::BaseError;
+use crate::opts::{Base, ColorWhen, Opt};
+use crate::{format_number, parse_number};
+use std::io::{self, IsTerminal, Read, Write};
+
+const WIDTHS: [Option<usize>; 6] = [None, Some(8), Some(16), Some(32), Some(64), Some(128)];
+
+/// Restores the original terminal settings when dropped
+struct RawMode {
+    original: libc::termios,
+}
+
+impl RawMode {
+    fn enable() -> Result<RawMode, BaseError> {
+        let mut termios = unsafe { std::mem::zeroed::<libc::termios>() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } != 0 {
+            return Err(BaseError::IoError {
+                message: "Unable to read terminal settings",
+            });
+        }
+
+        let original = termios;
+        termios.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG);
+        termios.c_cc[libc::VMIN] = 1;
+        termios.c_cc[libc::VTIME] = 0;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) } != 0 {
+            return Err(BaseError::IoError {
+                message: "Unable to enable raw terminal mode",
+            });
+        }
+
+        Ok(RawMode { original })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original) };
+        print!("\x1b[2J\x1b[H\x1b[?25h");
+        let _ = io::stdout().flush();
+    }
+}
+
+struct State {
+    input: String,
+    grouping: bool,
+    width: usize,
+}
+
+/// Runs a full-screen dashboard showing the typed value in every base as it changes.
+/// Tab toggles digit grouping, Ctrl-W cycles the bit width and Ctrl-C or Ctrl-D quits.
+pub fn run(opt: &Opt) -> Result<(), BaseError> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err(BaseError::ArgError {
+            message: "The dashboard requires an interactive terminal",
+        });
+    }
+
+    let _raw = RawMode::enable()?;
+    let mut state = State {
+        input: String::new(),
+        grouping: false,
+        width: 0,
+    };
+
+    let mut stdin = io::stdin().lock();
+    let mut byte = [0u8; 1];
+    loop {
+        render(opt, &state)?;
+        if stdin.read(&mut byte).map_err(|_| BaseError::IoError {
+            message: "Unable to read from stdin",
+        })? == 0
+        {
+            return Ok(());
+        }
+
+        match byte[0] {
+            // Ctrl-C, Ctrl-D
+            0x03 | 0x04 => return Ok(()),
+            b'\t' => state.grouping = !state.grouping,
+            // Ctrl-W
+            0x17 => state.width = (state.width + 1) % WIDTHS.len(),
+            // Backspace, Delete
+            0x08 | 0x7f => {
+                state.input.pop();
+            }
+            // Escape sequences such as arrow keys are ignored
+            0x1b => {
+                let mut sequence = [0u8; 2];
+                let _ = stdin.read(&mut sequence);
+            }
+            c if (0x20..0x7f).contains(&c) => state.input.push(c as char),
+            _ => {}
+        }
+    }
+}
+
+fn render(opt: &Opt, state: &State) -> Result<(), BaseError> {
+    let mut opt = opt.clone();
+    opt.width = WIDTHS[state.width];
+    // diagnostics on stderr would be drawn over the screen, and the digits are grouped
+    // here, so neither is wanted while rendering
+    opt.quiet = true;
+    opt.color = ColorWhen::Never;
+
+    let mut screen = String::from("\x1b[2J\x1b[H");
+    screen.push_str("changebase\r\n\r\n");
+    screen.push_str(&format!("  value: {}\r\n\r\n", state.input));
+
+    let mut status = String::new();
+    for base in Base::all() {
+        let digits = if state.input.is_empty() {
+            String::new()
+        } else {
+            match parse_number(&opt, &state.input, None)
+                .and_then(|parsed| format_number(&opt, &parsed, base.clone()))
+            {
+                Ok(digits) if state.grouping => group(&digits, default_group_size(&base), " "),
+                Ok(digits) => digits,
+                Err(e) => {
+                    status = e.to_string();
+                    String::new()
+                }
+            }
+        };
+        screen.push_str(&format!("  {:<12} {}\r\n", base.repr(), digits));
+    }
+
+    let width = match opt.width {
+        Some(width) => format!("{} bits", width),
+        None => "arbitrary".to_string(),
+    };
+    screen.push_str(&format!(
+        "\r\n  grouping: {}  width: {}  {}\r\n",
+        if state.grouping { "on" } else { "off" },
+        width,
+        status
+    ));
+    screen.push_str("\r\n  Tab: grouping  Ctrl-W: width  Ctrl-C: quit");
+    screen.push_str(&format!("\x1b[3;{}H", 10 + state.input.len()));
+
+    let mut stdout = io::stdout();
+    stdout
+        .write_all(screen.as_bytes())
+        .and_then(|_| stdout.flush())
+        .map_err(|_| BaseError::IoError {
+            message: "Unable to write to stdout",
+        })
+}
@@ -0,0 +1,75 @@
+use crate::base::Value;
+use crate::errors::{BaseError, Syntax};
+use crate::opts::{Base, Opt};
+use crate::text::escape_text;
+use crate::{format_output, parse_number, Number, Parsed};
+use num::{bigint::BigInt, ToPrimitive};
+
+/// Shows a code point, given as U+XXXX, the character itself or a number, in the output
+/// base or all bases, along with its UTF-8 and UTF-16 encodings
+pub fn run(opt: &Opt, value: &str) -> Result<(), BaseError> {
+    let c = parse_char(opt, value)?;
+    let parsed = Parsed {
+        base: Base::Hex,
+        num: Number::Integer(Value::new(BigInt::from(c as u32))),
+        width: None,
+        signed: false,
+    };
+
+    let mut utf8 = [0; 4];
+    let mut utf16 = [0; 2];
+    println!("Code point: U+{:04X}", c as u32);
+    println!(
+        "Character:  {}",
+        escape_text(c.encode_utf8(&mut utf8).as_bytes())
+    );
+    println!("{}", format_output(opt, &parsed)?);
+    println!(
+        "UTF-8:  {}",
+        hex_units(c.encode_utf8(&mut utf8).bytes().map(u32::from), 2)
+    );
+    println!(
+        "UTF-16: {}",
+        hex_units(
+            c.encode_utf16(&mut utf16).iter().map(|unit| *unit as u32),
+            4
+        )
+    );
+    Ok(())
+}
+
+fn parse_char(opt: &Opt, value: &str) -> Result<char, BaseError> {
+    let error = BaseError::InvalidSyntax {
+        syntax: Syntax::Character,
+    };
+
+    let code = if let Some(digits) = value
+        .strip_prefix("U+")
+        .or_else(|| value.strip_prefix("u+"))
+    {
+        u32::from_str_radix(digits, 16).map_err(|_| error)?
+    } else {
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if !c.is_ascii_digit() => return Ok(c),
+            _ => parse_number(opt, value, None)?
+                .integer()?
+                .clone()
+                .into_inner()
+                .to_u32()
+                .ok_or(BaseError::OverflowError {
+                    message: "Code points range from 0 to 10FFFF",
+                })?,
+        }
+    };
+    char::from_u32(code).ok_or(BaseError::OverflowError {
+        message: "Code points range from 0 to 10FFFF, excluding surrogates",
+    })
+}
+
+fn hex_units(units: impl Iterator<Item = u32>, digits: usize) -> String {
+    units
+        .map(|unit| format!("{:0width$x}", unit, width = digits))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
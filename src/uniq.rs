@@ -0,0 +1,45 @@
+use crate::errors::BaseError;
+use crate::opts::Opt;
+use crate::{format_integer, read_numbers};
+use std::collections::HashMap;
+use std::io::{self, BufWriter, Write};
+
+/// Prints each distinct number once, in the order first read, so the same number
+/// written in different bases is only printed once. Numbers are printed as first
+/// written, or in the output base if one is given, and with `count`, after the number
+/// of times they were read.
+pub fn run(opt: &Opt, count: bool) -> Result<(), BaseError> {
+    let mut counts = HashMap::new();
+    let mut distinct = Vec::new();
+    for (value, num) in read_numbers(opt)? {
+        let seen = counts.entry(num.clone()).or_insert(0usize);
+        if *seen == 0 {
+            distinct.push((value, num));
+        }
+        *seen += 1;
+    }
+
+    let output = opt.get_output();
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    for (value, num) in distinct {
+        let times = counts[&num];
+        let line = match &output {
+            Some(base) => format_integer(opt, num, base.clone())?,
+            None => value,
+        };
+        if count {
+            // laid out as with uniq -c
+            write!(writer, "{:>7} ", times)
+        } else {
+            Ok(())
+        }
+        .and_then(|_| writeln!(writer, "{}", line))
+        .map_err(|_| BaseError::IoError {
+            message: "Unable to write to stdout",
+        })?;
+    }
+    writer.flush().map_err(|_| BaseError::IoError {
+        message: "Unable to write to stdout",
+    })
+}
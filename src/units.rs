@@ -0,0 +1,132 @@
+use crate::base::Value;
+use crate::errors::{BaseError, Syntax};
+use crate::opts::{Base, Opt};
+use crate::{format_number, parse_number, Number, Parsed};
+use num::{bigint::BigInt, Integer, Num, Signed, Zero};
+
+/// Ether units and their number of decimals
+const ETHER_UNITS: [(&str, u32); 3] = [("wei", 0), ("gwei", 9), ("ether", 18)];
+
+/// Most decimals a token may have, as ERC-20 stores them in a uint8
+const MAX_DECIMALS: u32 = 255;
+
+/// Prints the amount in every unit, or only the `to` unit. Amounts with a unit suffix,
+/// such as 1.5eth or 30gwei, are decimal; bare amounts are integers of the smallest unit
+/// in any base. With `decimals`, the units are the raw integer and whole tokens.
+pub fn run(
+    opt: &Opt,
+    value: &str,
+    to: Option<&str>,
+    decimals: Option<u32>,
+) -> Result<(), BaseError> {
+    let units: Vec<(&str, u32)> = match decimals {
+        Some(decimals) if decimals > MAX_DECIMALS => {
+            return Err(BaseError::ArgError {
+                message: "Decimals: must be at most 255",
+            })
+        }
+        Some(decimals) => vec![("raw", 0), ("token", decimals)],
+        None => ETHER_UNITS.to_vec(),
+    };
+    let amount = parse_amount(opt, value, &units)?;
+
+    let shown: Vec<(&str, u32)> = match to {
+        Some(to) => vec![find_unit(&units, to).ok_or(BaseError::ArgError {
+            message: "Unknown unit, use wei, gwei or ether, or raw or token with --decimals",
+        })?],
+        None => units,
+    };
+
+    let lines = shown
+        .iter()
+        .map(|(name, decimals)| {
+            let amount = format_amount(opt, &amount, *decimals)?;
+            Ok(match to {
+                Some(_) => amount,
+                None => format!("{}: {}", name, amount),
+            })
+        })
+        .collect::<Result<Vec<String>, BaseError>>()?;
+    println!("{}", lines.join("\n"));
+    Ok(())
+}
+
+/// Finds a unit by name in either case, also accepting eth for ether
+fn find_unit(units: &[(&'static str, u32)], name: &str) -> Option<(&'static str, u32)> {
+    let name = name.to_ascii_lowercase();
+    let name = if name == "eth" { "ether" } else { &name };
+    units.iter().find(|(unit, _)| *unit == name).copied()
+}
+
+/// Reads an amount as an integer of the smallest unit
+fn parse_amount(
+    opt: &Opt,
+    value: &str,
+    units: &[(&'static str, u32)],
+) -> Result<BigInt, BaseError> {
+    let split = value
+        .trim_end_matches(|c: char| c.is_ascii_alphabetic())
+        .len();
+    let (number, suffix) = value.split_at(split);
+    let decimals = match find_unit(units, suffix).filter(|_| !number.is_empty()) {
+        Some((_, decimals)) => decimals,
+        None => {
+            return Ok(parse_number(opt, value, None)?
+                .integer()?
+                .clone()
+                .into_inner())
+        }
+    };
+
+    let error = BaseError::InvalidSyntax {
+        syntax: Syntax::Units,
+    };
+    let (negative, number) = match number.strip_prefix('-') {
+        Some(number) => (true, number),
+        None => (false, number),
+    };
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if fraction.len() > decimals as usize
+        || !(integer.chars().chain(fraction.chars())).all(|c| c.is_ascii_digit())
+        || integer.len() + fraction.len() == 0
+    {
+        return Err(error);
+    }
+    let digits = format!(
+        "0{}{:0<width$}",
+        integer,
+        fraction,
+        width = decimals as usize
+    );
+    let amount = BigInt::from_str_radix(&digits, 10).map_err(|_| error)?;
+    Ok(if negative { -amount } else { amount })
+}
+
+/// Writes an amount of the smallest unit in a unit with the given decimals. Whole
+/// amounts of the smallest unit use the output base; others are decimal with trailing
+/// zeros dropped.
+fn format_amount(opt: &Opt, amount: &BigInt, decimals: u32) -> Result<String, BaseError> {
+    if decimals == 0 {
+        let parsed = Parsed {
+            base: Base::Dec,
+            num: Number::Integer(Value::new(amount.clone())),
+            width: None,
+            signed: false,
+        };
+        return format_number(opt, &parsed, opt.get_output().unwrap_or(Base::Dec));
+    }
+
+    let scale = num::pow(BigInt::from(10u32), decimals as usize);
+    let (integer, fraction) = amount.abs().div_rem(&scale);
+    let sign = if amount.is_negative() { "-" } else { "" };
+    if fraction.is_zero() {
+        return Ok(format!("{}{}", sign, integer));
+    }
+    let fraction = format!("{:0>width$}", fraction, width = decimals as usize);
+    Ok(format!(
+        "{}{}.{}",
+        sign,
+        integer,
+        fraction.trim_end_matches('0')
+    ))
+}
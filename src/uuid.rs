@@ -0,0 +1,93 @@
+use crate::errors::BaseError;
+use num::{bigint::BigInt, Num, ToPrimitive};
+
+/// Lengths of the hyphen-separated groups of a canonical UUID
+const GROUPS: [usize; 5] = [8, 4, 4, 4, 12];
+
+/// Writes the value as a canonical lowercase UUID, e.g. 123e4567-e89b-12d3-a456-426614174000
+pub fn to_uuid(value: &BigInt) -> Result<String, BaseError> {
+    let value = value.to_u128().ok_or(BaseError::OverflowError {
+        message: "UUIDs range from 0 to 2^128 - 1",
+    })?;
+    let digits = format!("{:032x}", value);
+
+    let mut groups = Vec::new();
+    let mut start = 0;
+    for length in GROUPS {
+        groups.push(&digits[start..start + length]);
+        start += length;
+    }
+    Ok(groups.join("-"))
+}
+
+/// Reads a UUID in either case, hyphenated or as 32 bare hex digits. Surrounding braces
+/// and a urn:uuid: prefix are ignored.
+pub fn parse_uuid(value: &str) -> Option<BigInt> {
+    let value = value.strip_prefix("urn:uuid:").unwrap_or(value);
+    let value = match value.strip_prefix('{') {
+        Some(inner) => inner.strip_suffix('}')?,
+        None => value,
+    };
+
+    let groups: Vec<&str> = value.split('-').collect();
+    let lengths: Vec<usize> = groups.iter().map(|group| group.len()).collect();
+    if lengths != GROUPS && lengths != [32] {
+        return None;
+    }
+    let digits = groups.concat();
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    BigInt::from_str_radix(&digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UUID: &str = "123e4567-e89b-12d3-a456-426614174000";
+    const VALUE: u128 = 0x123e4567_e89b_12d3_a456_426614174000;
+
+    #[test]
+    fn round_trips() {
+        assert_eq!(to_uuid(&BigInt::from(VALUE)).unwrap(), UUID);
+        assert_eq!(parse_uuid(UUID), Some(BigInt::from(VALUE)));
+        assert_eq!(
+            to_uuid(&BigInt::from(0)).unwrap(),
+            "00000000-0000-0000-0000-000000000000"
+        );
+        assert_eq!(
+            to_uuid(&BigInt::from(u128::MAX)).unwrap(),
+            "ffffffff-ffff-ffff-ffff-ffffffffffff"
+        );
+        assert!(to_uuid(&(BigInt::from(1) << 128)).is_err());
+        assert!(to_uuid(&BigInt::from(-1)).is_err());
+    }
+
+    #[test]
+    fn reads_every_form() {
+        for uuid in [
+            "123e4567e89b12d3a456426614174000",
+            "123E4567-E89B-12D3-A456-426614174000",
+            "{123e4567-e89b-12d3-a456-426614174000}",
+            "urn:uuid:123e4567-e89b-12d3-a456-426614174000",
+        ] {
+            assert_eq!(parse_uuid(uuid), Some(BigInt::from(VALUE)), "{}", uuid);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_uuids() {
+        for uuid in [
+            "",
+            "123e4567-e89b-12d3-a456-42661417400",
+            "123e4567e89b-12d3-a456-426614174000",
+            "123e4567-e89b-12d3-a456-4266141740000",
+            "123e4567-e89b-12d3-a456-42661417400g",
+            "{123e4567-e89b-12d3-a456-426614174000",
+            "123e4567e89b12d3a45642661417400",
+        ] {
+            assert_eq!(parse_uuid(uuid), None, "{:?}", uuid);
+        }
+    }
+}
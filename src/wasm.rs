@@ -0,0 +1,64 @@
+use crate::base::detect_base;
+use crate::opts::{Base, Opt};
+use crate::{error_message, format_number, parse_number};
+use structopt::StructOpt;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Converts the value from the `from` base, or its detected base, to the `to` base,
+/// reading it as the command line does, so expressions and fractions are accepted too.
+/// Bases are named as on the command line and errors are the messages it prints.
+#[wasm_bindgen]
+pub fn convert(value: &str, from: Option<String>, to: &str) -> Result<String, String> {
+    let mut opt = options();
+    opt.input = from.as_deref().map(base).transpose()?;
+    let parsed = parse_number(&opt, value, None).map_err(|e| error_message(&e))?;
+    format_number(&opt, &parsed, base(to)?).map_err(|e| error_message(&e))
+}
+
+/// Returns the name of the base the value would be read in, such as `hex`
+#[wasm_bindgen]
+pub fn detect(value: &str) -> Result<String, String> {
+    let opt = options();
+    detect_base(value, opt.detect_strategy())
+        .map(|base| base.name().to_string())
+        .map_err(|e| error_message(&e))
+}
+
+/// Returns the defaults of the command line, without the config file, which a browser
+/// does not have
+fn options() -> Opt {
+    Opt::from_iter(&["changebase", "--quiet"])
+}
+
+fn base(name: &str) -> Result<Base, String> {
+    name.parse().map_err(|_| format!("Unknown base {}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts() {
+        assert_eq!(convert("0xff", None, "dec"), Ok("255".to_string()));
+        assert_eq!(
+            convert("11", Some("bin".to_string()), "hex"),
+            Ok("3".to_string())
+        );
+        assert_eq!(convert("1 << 4", None, "hex"), Ok("10".to_string()));
+        assert!(convert("zz", Some("hex".to_string()), "dec")
+            .unwrap_err()
+            .contains("digit 'z'"));
+        assert_eq!(
+            convert("1", None, "nope"),
+            Err("Unknown base nope".to_string())
+        );
+    }
+
+    #[test]
+    fn detects() {
+        assert_eq!(detect("0b101"), Ok("bin".to_string()));
+        assert_eq!(detect("ff"), Ok("hex".to_string()));
+        assert!(detect("").is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use crate::base::{prefix, Value};
+use crate::errors::BaseError;
+use crate::opts::{Base, Opt};
+use crate::Parsed;
+use num::{bigint::BigInt, Integer, One, Zero};
+
+const WIDTHS: [usize; 4] = [8, 16, 32, 64];
+
+/// Shows the value wrapped to each common unsigned and signed integer type, printed in
+/// the output base or decimal. Types that cannot hold the value are marked as truncated.
+pub fn table(opt: &Opt, parsed: &Parsed) -> Result<String, BaseError> {
+    let num = parsed.integer()?.clone().into_inner();
+    let output = opt.get_output().unwrap_or(Base::Dec);
+
+    let mut lines = Vec::new();
+    for width in WIDTHS {
+        let modulus = BigInt::one() << width;
+        let unsigned = num.mod_floor(&modulus);
+        let half = &modulus >> 1usize;
+        let signed = if unsigned >= half {
+            &unsigned - &modulus
+        } else {
+            unsigned.clone()
+        };
+
+        for (name, wrapped) in [("u", unsigned), ("i", signed)] {
+            let digits = Value::new(wrapped.clone()).to_base(output.clone())?;
+            let digits = match opt.get_prefix_style() {
+                Some(style) => prefix(&digits, output.prefix(&style)),
+                None => digits,
+            };
+            let marker = if wrapped == num { "" } else { " (truncated)" };
+            lines.push(format!(
+                "{:<4} {}{}",
+                format!("{}{}:", name, width),
+                digits,
+                marker
+            ));
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Checks that the value fits in an integer type such as u8, i32 or u128, failing
+/// with the range of the type and how far outside of it the value is otherwise
+pub fn check_fits(opt: &Opt, parsed: &Parsed, ty: &str) -> Result<(), BaseError> {
+    let (signed, bits) = parse_type(ty)?;
+    let num = parsed.integer()?.clone().into_inner();
+    let (min, max) = if signed {
+        let half = BigInt::one() << (bits - 1);
+        (-&half, half - 1)
+    } else {
+        (BigInt::zero(), (BigInt::one() << bits) - 1)
+    };
+
+    let over = if num > max {
+        &num - &max
+    } else if num < min {
+        &min - &num
+    } else {
+        return Ok(());
+    };
+    if !opt.quiet {
+        eprintln!("{} holds {} to {}, value is {} outside", ty, min, max, over);
+    }
+    Err(BaseError::Overflow {
+        bits_required: parsed.integer()?.bits_required(signed),
+    })
+}
+
+/// Parses an integer type name into its signedness and width
+fn parse_type(ty: &str) -> Result<(bool, usize), BaseError> {
+    let (signed, bits) = if let Some(bits) = ty.strip_prefix('u') {
+        (false, bits)
+    } else if let Some(bits) = ty.strip_prefix('i') {
+        (true, bits)
+    } else {
+        (false, "")
+    };
+    let bits = match bits {
+        "size" => Some(64),
+        bits => bits.parse::<usize>().ok().filter(|bits| *bits > 0),
+    };
+    bits.map(|bits| (signed, bits)).ok_or(BaseError::ArgError {
+        message: "Integer types are written as u8, i32, u128 or usize",
+    })
+}
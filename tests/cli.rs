@@ -0,0 +1,409 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Runs the tool with the arguments and stdin, returning its stdout and exit code
+fn run(args: &[&str], stdin: &str) -> (String, i32) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_changebase"))
+        .args(args)
+        .env("HOME", env!("CARGO_TARGET_TMPDIR"))
+        .env_remove("XDG_CONFIG_HOME")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    (
+        String::from_utf8(output.stdout).unwrap(),
+        output.status.code().unwrap(),
+    )
+}
+
+fn stdout(args: &[&str]) -> String {
+    let (stdout, code) = run(args, "");
+    assert_eq!(code, 0, "changebase {:?} failed", args);
+    stdout
+}
+
+#[test]
+fn converts_fractions() {
+    assert_eq!(stdout(&["-q", "--ob", "3.14159"]), "11.0010010000\n");
+    assert_eq!(stdout(&["3.14159", "--ob"]), "11.0010010000\n");
+}
+
+#[test]
+fn filters_bare_words_in_the_from_base() {
+    let (output, _) = run(&["filter", "--from", "hex"], "dead 0xbeef eax v1.2.3\n");
+    assert_eq!(output, "57005 48879 eax v1.2.3\n");
+}
+
+#[test]
+fn converts_hex_floats() {
+    assert_eq!(stdout(&["-q", "--od", "0x1.8p0"]), "1.5\n");
+    assert_eq!(stdout(&["-q", "--od", "0x1.8p-1"]), "0.75\n");
+}
+
+#[test]
+fn serves_json_without_color() {
+    let request = "{\"id\":1,\"method\":\"convert\",\"value\":\"65535\",\"to\":\"bin\"}\n";
+    let (output, code) = run(&["--color", "always", "serve", "--stdio"], request);
+    assert_eq!(code, 0);
+    assert_eq!(output, "{\"id\":1,\"result\":\"1111111111111111\"}\n");
+}
+
+#[test]
+fn reads_options_after_the_subcommand() {
+    assert_eq!(
+        stdout(&["-q", "units", "1.5eth", "--o", "wei", "--oh"]),
+        "14d1120d7b160000\n"
+    );
+    assert_eq!(stdout(&["op", "and", "0xff", "0x0f", "--oh", "-q"]), "f\n");
+    assert_eq!(
+        stdout(&["-q", "calc", "0xff", "+", "1", "--ob"]),
+        "100000000\n"
+    );
+}
+
+#[test]
+fn reads_negative_operands_of_subcommands() {
+    assert_eq!(
+        stdout(&["-q", "cmp", "-5", "3"]),
+        "-5 < 3\ndifference: -8\nxor: -8\n"
+    );
+    assert_eq!(stdout(&["-q", "seq", "3", "1", "-1"]), "3\n2\n1\n");
+}
+
+#[test]
+fn masks_have_no_signed_reading() {
+    let output = stdout(&["-q", "cidr", "/24"]);
+    assert!(output.contains("hex: ffffff00"));
+    assert!(!output.contains("signed"));
+}
+
+#[test]
+fn reads_dumps_from_stdin() {
+    let xxd = "00000000: 4865 6c6c 6f2c 2077 6f72 6c64 210a       Hello, world!.\n";
+    assert_eq!(
+        run(&["-q", "--oh"], xxd).0,
+        "48656c6c6f2c20776f726c64210a\n"
+    );
+    let hexdump = "00000000  48 65 6c 6c 6f  |Hello|\n00000005\n";
+    assert_eq!(run(&["-q", "--oh"], hexdump).0, "48656c6c6f\n");
+    // whitespace-separated numbers are still read one by one
+    assert_eq!(
+        run(&["-q", "--od"], "101010 11110000\n").0,
+        "101010\n11110000\n"
+    );
+}
+
+#[test]
+fn command_line_overrides_the_config_file() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("config-override");
+    std::fs::create_dir_all(dir.join("changebase")).unwrap();
+    std::fs::write(
+        dir.join("changebase").join("config.toml"),
+        "output = \"hex\"\ncolor = \"always\"\n",
+    )
+    .unwrap();
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_changebase"))
+            .args(args)
+            .env("XDG_CONFIG_HOME", &dir)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let colored = run(&["-q", "--id", "--group=2", "65535"]);
+    assert!(colored.contains('\u{1b}'), "{:?}", colored);
+    assert_eq!(
+        run(&["-q", "--id", "--group=2", "--color", "auto", "65535"]),
+        "ff_ff\n"
+    );
+    assert_eq!(
+        run(&["-q", "--id", "--ob", "--color", "never", "5"]),
+        "101\n"
+    );
+}
+
+#[test]
+fn names_values_with_no_flags_set() {
+    assert_eq!(
+        stdout(&["-q", "--flags", "READ=0x1,WRITE=0x2", "0"]),
+        "none\n"
+    );
+    assert_eq!(stdout(&["-q", "--flags", "NONE=0,READ=0x1", "0"]), "NONE\n");
+    assert_eq!(stdout(&["-q", "--flags", "NONE=0,READ=0x1", "1"]), "READ\n");
+}
+
+#[test]
+fn rejects_huge_token_decimals() {
+    for decimals in ["256", "4000000000"] {
+        let (output, code) = run(&["-q", "units", "1token", "--decimals", decimals], "");
+        assert_eq!((output.as_str(), code), ("", 1), "--decimals {}", decimals);
+    }
+    assert_eq!(
+        stdout(&["-q", "units", "1token", "--decimals", "255", "--to", "raw"]).len(),
+        257
+    );
+}
+
+#[test]
+fn rejects_huge_bit_indexes() {
+    for op in ["set", "clear", "toggle", "test"] {
+        assert_eq!(
+            run(&["-q", "bit", op, "99999999999", "0"], ""),
+            (String::new(), 1)
+        );
+    }
+    assert_eq!(stdout(&["-q", "--oh", "bit", "set", "16", "0"]), "10000\n");
+}
+
+#[test]
+fn rejects_huge_bit_ranges() {
+    for args in [
+        &["mask", "99999999999:0"][..],
+        &["mask", "0..99999999999"],
+        &["bit", "extract", "99999999999:99999999990", "1"],
+        &["bit", "insert", "99999999999:0", "1", "0"],
+    ] {
+        assert_eq!(run(args, ""), (String::new(), 1), "{:?}", args);
+    }
+    assert_eq!(stdout(&["--oh", "mask", "0..16777216"]).len(), 4194305);
+}
+
+#[test]
+fn rejects_huge_widths() {
+    for args in [
+        &["--width", "99999999999", "1"][..],
+        &["--width", "16777217", "--signed", "1"],
+        &["info", "1", "--width", "99999999999"],
+        &["bit", "set", "1", "0", "--width", "99999999999"],
+    ] {
+        assert_eq!(run(args, ""), (String::new(), 1), "{:?}", args);
+    }
+    assert_eq!(
+        stdout(&["-q", "--width", "16", "--signed", "--od", "0xffff"]),
+        "-1\n"
+    );
+}
+
+#[test]
+fn rejects_huge_padding() {
+    for args in [
+        &["--pad", "99999999999", "1"][..],
+        &["--bits", "99999999999", "--oh", "1"],
+        &["bit", "set", "1", "0", "--pad", "99999999999"],
+    ] {
+        assert_eq!(run(args, ""), (String::new(), 1), "{:?}", args);
+    }
+    assert_eq!(stdout(&["-q", "--pad", "8", "--oh", "255"]), "000000ff\n");
+}
+
+#[test]
+fn sorts_mixed_bases() {
+    let input = "0xff\n10\n0b101\n-3\n255\n";
+    assert_eq!(
+        run(&["-q", "sort"], input),
+        ("-3\n0b101\n10\n0xff\n255\n".to_string(), 0)
+    );
+    assert_eq!(run(&["-q", "--oh", "sort"], input).0, "-3\n5\na\nff\nff\n");
+    assert_eq!(run(&["-q", "sort"], ""), (String::new(), 0));
+    assert_eq!(run(&["-q", "sort"], "  \n5\n\n"), ("5\n".to_string(), 0));
+    assert_eq!(run(&["-q", "sort"], "1\n0xzz\n").1, 2);
+}
+
+#[test]
+fn summarizes_mixed_bases() {
+    assert_eq!(
+        run(&["-q", "stats"], "0xff\n10\n0b101\n-3\n"),
+        (
+            "count: 4\nmin: -3\nmax: 255\nsum: 267\nmean: 66.75\n".to_string(),
+            0
+        )
+    );
+    assert_eq!(run(&["-q", "stats"], ""), ("count: 0\n".to_string(), 0));
+    assert_eq!(run(&["-q", "stats"], "1.5\n").1, 1);
+}
+
+#[test]
+fn counts_values_equal_across_bases_once() {
+    let input = "0xff\n255\n0o377\n10\n0xa\n";
+    assert_eq!(run(&["-q", "uniq"], input), ("0xff\n10\n".to_string(), 0));
+    assert_eq!(
+        run(&["-q", "uniq", "-c"], input).0,
+        "      3 0xff\n      2 10\n"
+    );
+    assert_eq!(run(&["-q", "uniq"], ""), (String::new(), 0));
+}
+
+#[test]
+fn diffs_files_by_value() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let write = |name: &str, text: &str| {
+        let path = dir.join(name);
+        std::fs::write(&path, text).unwrap();
+        path.to_str().unwrap().to_string()
+    };
+    let a = write("diff-a.txt", "0xff\n1\n-0x2\n");
+    let b = write("diff-b.txt", "255\n0b1\n3\n7\n");
+    let empty = write("diff-empty.txt", "");
+    assert_eq!(
+        run(&["-q", "diff", &a, &b], ""),
+        (format!("line 3: -0x2 != 3\nonly in {}: 7\n", b), 1)
+    );
+    assert_eq!(run(&["-q", "diff", &a, &a], ""), (String::new(), 0));
+    assert_eq!(run(&["-q", "diff", &empty, &empty], ""), (String::new(), 0));
+    assert_eq!(
+        run(&["-q", "diff", &empty, &a], ""),
+        (
+            format!(
+                "only in {a}: 0xff\nonly in {a}: 1\nonly in {a}: -0x2\n",
+                a = a
+            ),
+            1
+        )
+    );
+}
+
+#[test]
+fn compares_values_in_their_own_bases() {
+    assert_eq!(
+        stdout(&["-q", "cmp", "0xff", "0o377"]),
+        "0xff == 0o377\ndifference: 0\nxor: 0\n"
+    );
+    assert_eq!(
+        stdout(&["-q", "cmp", "3", "0x10"]),
+        "3 < 0x10\ndifference: -13\nxor: 19\n"
+    );
+    assert_eq!(
+        stdout(&["-q", "cmp", "-1", "0xff"]),
+        "-1 < 0xff\ndifference: -256\nxor: -256\n"
+    );
+    assert_eq!(run(&["-q", "cmp", "0xzz", "1"], "").1, 2);
+}
+
+#[test]
+fn checks_comparisons_across_bases() {
+    assert_eq!(
+        run(&["-q", "check", "0xff == 0o377"], ""),
+        (String::new(), 0)
+    );
+    assert_eq!(run(&["-q", "check", "0xff < 0b1"], ""), (String::new(), 1));
+    assert_eq!(
+        run(&["-v", "check", "0x10 >= 0o20"], ""),
+        ("0x10 >= 0o20: true\n".to_string(), 0)
+    );
+    assert_eq!(run(&["-q", "check", "1 !="], ""), (String::new(), 1));
+    assert_eq!(run(&["-q", "check", "0xzz == 1"], ""), (String::new(), 2));
+}
+
+#[test]
+fn diffs_bits_of_mixed_bases() {
+    assert_eq!(
+        stdout(&["-q", "bitdiff", "0b1010", "0xc"]),
+        "a:   0000 1010\nb:   0000 1100\nxor: 0000 0110\ndiffering bits: 1, 2\nhamming distance: 2\n"
+    );
+    assert!(stdout(&["-q", "bitdiff", "0", "0"])
+        .ends_with("differing bits: none\nhamming distance: 0\n"));
+    assert!(
+        stdout(&["-q", "bitdiff", "--width", "8", "-1", "1"]).ends_with("hamming distance: 7\n")
+    );
+    assert_eq!(run(&["-q", "bitdiff", "-1", "1"], ""), (String::new(), 1));
+}
+
+#[test]
+fn counts_down_with_negative_steps() {
+    assert_eq!(stdout(&["-q", "seq", "10", "0", "-3"]), "10\n7\n4\n1\n");
+    assert_eq!(
+        stdout(&["-q", "seq", "0x10", "0", "-4"]),
+        "10\nc\n8\n4\n0\n"
+    );
+    assert_eq!(stdout(&["-q", "seq", "2", "-2", "-2"]), "2\n0\n-2\n");
+    // a step away from the end prints nothing
+    assert_eq!(stdout(&["-q", "seq", "0", "10", "-1"]), "");
+    assert_eq!(run(&["-q", "seq", "1", "3", "0"], ""), (String::new(), 1));
+}
+
+#[test]
+fn exits_with_the_code_of_each_parse_error() {
+    assert_eq!(run(&["-q", "-i", "roman", "IIII"], "").1, 2);
+    assert_eq!(run(&["-q", "--as-float", "--id", "1.5"], "").1, 2);
+    assert_eq!(
+        run(
+            &[
+                "-q",
+                "-i",
+                "address",
+                "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"
+            ],
+            ""
+        )
+        .1,
+        2
+    );
+    assert_eq!(run(&["-q", "interpret", "zz.."], "").1, 3);
+}
+
+#[test]
+fn fails_detection_of_values_valid_in_no_base() {
+    assert_eq!(run(&["-q", "detect", "zz"], ""), (String::new(), 3));
+    assert_eq!(run(&["-q", "zz"], "").1, 3);
+    assert_eq!(run(&["-q", "--", "-1.z"], "").1, 3);
+    // a prefix names the base, so invalid digits after it fail parsing
+    assert_eq!(run(&["-q", "0xzz"], "").1, 2);
+    assert_eq!(stdout(&["-q", "detect", "ff"]), "hex\n");
+}
+
+#[test]
+fn aligns_table_columns_on_signs_and_radix_points() {
+    assert_eq!(
+        stdout(&["-q", "--table", "aligned", "--", "-1.5", "255", "0x10"]),
+        "input          bin     oct    dec    hex\n\
+         -1.5   -00000001.1  -001.4   -1.5  -01.8\n\
+         255     11111111     377    255     ff\n\
+         0x10    00010000     020     16     10\n"
+    );
+}
+
+#[test]
+fn fails_bit_position_queries_on_zero() {
+    assert_eq!(run(&["-q", "--msb", "0"], "").1, 1);
+    assert_eq!(run(&["-q", "--ctz", "0"], "").1, 1);
+    assert_eq!(stdout(&["-q", "--ctz", "8"]), "3\n");
+    assert!(stdout(&["info", "0"]).starts_with("Bits:           0\n"));
+}
+
+#[test]
+fn round_trips_mnemonics_with_the_built_in_wordlist() {
+    assert_eq!(
+        stdout(&["-q", "--mnemonic", "0xdeadbeef"]),
+        "hungry report target\n"
+    );
+    assert_eq!(
+        stdout(&["-q", "--oh", "mnemonic", "hungry", "report", "target"]),
+        "deadbeef\n"
+    );
+}
+
+#[test]
+fn picks_the_float_format_from_the_pattern_width() {
+    assert!(stdout(&["-q", "--as-float", "40600000"]).starts_with("Value:    3.5\n"));
+    assert!(stdout(&["-q", "--as-float", "3ff0000000000000"]).starts_with("Value:    1.0\n"));
+    assert!(stdout(&["-q", "--as-float", "--f64", "40600000"]).contains("subnormal"));
+    assert!(stdout(&["-q", "--as-float", "--id", "1080033280"]).starts_with("Value:    3.5\n"));
+}
+
+#[test]
+fn explains_detection_with_explain_on_either_side_of_the_subcommand() {
+    let explained = "Hexadecimal because it contains the digits a-f\n";
+    assert_eq!(stdout(&["-q", "--explain", "detect", "ff"]), explained);
+    assert_eq!(stdout(&["-q", "detect", "ff", "--explain"]), explained);
+    assert_eq!(stdout(&["-q", "detect", "ff"]), "hex\n");
+}